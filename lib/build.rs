@@ -99,6 +99,9 @@ fn main() {
         major > desired_major || (major == desired_major && minor >= desired_minor)
     };
 
+    if va_check_version(1, 22) {
+        println!("cargo::rustc-cfg=libva_1_22_or_higher");
+    }
     if va_check_version(1, 21) {
         println!("cargo::rustc-cfg=libva_1_21_or_higher");
     }
@@ -5,56 +5,127 @@
 //! Wrappers and helpers around `VABuffer`s.
 
 mod av1;
+mod buffer_2d;
+mod coded_bitstream;
+mod coded_status;
+mod context_parameter_update;
 mod enc_jpeg;
 mod enc_misc;
+mod enc_packed_header;
+mod enc_qp_map;
 mod h264;
 mod hevc;
 mod jpeg_baseline;
+mod mapped;
 mod mpeg2;
+mod mpeg4;
 mod proc_pipeline;
+mod vc1;
 mod vp8;
 mod vp9;
+#[cfg(libva_1_22_or_higher)]
+mod vvc;
 
 pub use av1::*;
+pub use buffer_2d::*;
+pub use coded_bitstream::*;
+pub use coded_status::*;
+pub use context_parameter_update::*;
 pub use enc_jpeg::*;
 pub use enc_misc::*;
+pub use enc_packed_header::*;
+pub use enc_qp_map::*;
 pub use h264::*;
 pub use hevc::*;
 pub use jpeg_baseline::*;
+pub use mapped::*;
 pub use mpeg2::*;
+pub use mpeg4::*;
 pub use proc_pipeline::*;
+pub use vc1::*;
 pub use vp8::*;
 pub use vp9::*;
+#[cfg(libva_1_22_or_higher)]
+pub use vvc::*;
 
+use std::cell::Cell;
 use std::rc::Rc;
 
 use log::error;
 
+use thiserror::Error;
+
 use crate::bindings;
 use crate::va_check;
 use crate::Context;
 use crate::VaError;
 
+/// Error type for [`Buffer::update`].
+#[derive(Debug, Error)]
+pub enum UpdateBufferError {
+    #[error("call to vaBufferInfo failed: {0}")]
+    VaError(#[from] VaError),
+    #[error("error while mapping the buffer: {0}")]
+    MapBufferError(#[from] MapBufferError),
+    #[error("the new content is not of the same type as the buffer")]
+    TypeMismatch,
+    #[error("the buffer has {0} bytes but the new content has {1}")]
+    SizeMismatch(usize, usize),
+    #[error("the new content has no data to write")]
+    NoContent,
+}
+
 /// Wrapper type representing a buffer created with `vaCreateBuffer`.
 pub struct Buffer {
     context: Rc<Context>,
     id: bindings::VABufferID,
+    /// Whether the buffer is currently mapped through [`Buffer::map`] or [`Buffer::map_mut`].
+    mapped: Cell<bool>,
+    /// Content the buffer has been created or last updated with, if its data points to memory
+    /// outside of the buffer that the driver reads when the buffer is rendered. It is kept alive
+    /// until the buffer is destroyed.
+    backing: Cell<Option<BufferType>>,
 }
 
 impl Buffer {
     /// Creates a new buffer by wrapping a `vaCreateBuffer` call. This is just a helper for
     /// [`Context::create_buffer`].
     pub(crate) fn new(context: Rc<Context>, mut type_: BufferType) -> Result<Self, VaError> {
-        let mut buffer_id = 0;
+        let (ptr, size, nb_elements) = Self::raw_parts(&mut type_);
+
+        // An empty array would reach `vaCreateBuffer` with no element and a dangling pointer.
+        if nb_elements == 0 {
+            va_check(bindings::VA_STATUS_ERROR_INVALID_PARAMETER as bindings::VAStatus)?;
+        }
+
+        // Safe because `ptr` and `size` are ensured to be correct, as `ptr` is just a cast to
+        // `*c_void` from a Rust struct, and `size` is computed from `std::mem::size_of_val`.
+        let buffer = unsafe { Self::new_raw(context, type_.inner(), size, nb_elements, ptr) }?;
+
+        // Moving `type_` does not move the heap storage its data points to.
+        if type_.has_external_data() {
+            buffer.backing.set(Some(type_));
+        }
+
+        Ok(buffer)
+    }
 
-        /* we send all slices parameters as a single array in H264, AV1 */
+    /// Returns the pointer to the data of `type_`, the size of a single element and the number of
+    /// elements, as expected by `vaCreateBuffer`.
+    fn raw_parts(type_: &mut BufferType) -> (*mut std::ffi::c_void, usize, usize) {
+        /* we send all slices parameters of a picture as a single array */
         let nb_elements = match type_ {
-            BufferType::SliceParameter(SliceParameter::H264(ref mut params)) => {
-                params.inner_mut().len()
-            }
-            BufferType::SliceParameter(SliceParameter::AV1(ref mut params)) => {
-                params.inner_mut().len()
-            }
+            BufferType::SliceParameter(ref params) => params.nb_elements(),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::Alf(ref mut wrapper) => wrapper.inner_mut().len(),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::Lmcs(ref mut wrapper) => wrapper.inner_mut().len(),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::SubPic(ref mut wrapper) => wrapper.inner_mut().len(),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::Tile(ref mut wrapper) => wrapper.inner_mut().len(),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::SliceStruct(ref mut wrapper) => wrapper.inner_mut().len(),
             _ => 1,
         };
 
@@ -100,44 +171,70 @@ impl Buffer {
                     wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
-            },
-
-            BufferType::SliceParameter(ref mut slice_param) => match slice_param {
-                SliceParameter::MPEG2(ref mut wrapper) => (
+                PictureParameter::VC1(ref mut wrapper) => (
                     wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
-                SliceParameter::VP8(ref mut wrapper) => (
+                PictureParameter::MPEG4(ref mut wrapper) => (
                     wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
-                SliceParameter::VP9(ref mut wrapper) => (
+                #[cfg(libva_1_22_or_higher)]
+                PictureParameter::VVC(ref mut wrapper) => (
                     wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
+            },
+
+            BufferType::SliceParameter(ref mut slice_param) => match slice_param {
+                SliceParameter::MPEG2(ref mut wrapper) => (
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VASliceParameterBufferMPEG2>(),
+                ),
+                SliceParameter::VP8(ref mut wrapper) => (
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VASliceParameterBufferVP8>(),
+                ),
+                SliceParameter::VP9(ref mut wrapper) => (
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VASliceParameterBufferVP9>(),
+                ),
                 SliceParameter::H264(ref mut wrapper) => (
                     wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
                     std::mem::size_of::<bindings::VASliceParameterBufferH264>(),
                 ),
                 SliceParameter::HEVC(ref mut wrapper) => (
-                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
-                    std::mem::size_of_val(wrapper.inner_mut()),
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VASliceParameterBufferHEVC>(),
                 ),
                 SliceParameter::HEVCRext(ref mut wrapper) => (
-                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
-                    std::mem::size_of_val(wrapper.inner_mut()),
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VASliceParameterBufferHEVCRext>(),
                 ),
                 SliceParameter::AV1(ref mut wrapper) => (
                     wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
                     std::mem::size_of::<bindings::VASliceParameterBufferAV1>(),
                 ),
                 SliceParameter::JPEGBaseline(ref mut wrapper) => (
-                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
-                    std::mem::size_of_val(wrapper.inner_mut()),
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VASliceParameterBufferJPEGBaseline>(),
                 ),
                 SliceParameter::EncJpeg(ref mut wrapper) => (
-                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
-                    std::mem::size_of_val(wrapper.inner_mut()),
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VAEncSliceParameterBufferJPEG>(),
+                ),
+                SliceParameter::VC1(ref mut wrapper) => (
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VASliceParameterBufferVC1>(),
+                ),
+                SliceParameter::MPEG4(ref mut wrapper) => (
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VASliceParameterBufferMPEG4>(),
+                ),
+                #[cfg(libva_1_22_or_higher)]
+                SliceParameter::VVC(ref mut wrapper) => (
+                    wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                    std::mem::size_of::<bindings::VASliceParameterBufferVVC>(),
                 ),
             },
 
@@ -162,6 +259,15 @@ impl Buffer {
                     wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
+                IQMatrix::MPEG4(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                #[cfg(libva_1_22_or_higher)]
+                IQMatrix::VVC(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
             },
 
             BufferType::HuffmanTable(ref mut huffman_table) => match huffman_table {
@@ -201,6 +307,10 @@ impl Buffer {
                     wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
+                EncSequenceParameter::MPEG2(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
             },
 
             BufferType::EncPictureParameter(ref mut picture_param) => match picture_param {
@@ -224,6 +334,10 @@ impl Buffer {
                     wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
+                EncPictureParameter::MPEG2(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
             },
 
             BufferType::EncSliceParameter(ref mut slice_param) => match slice_param {
@@ -239,6 +353,10 @@ impl Buffer {
                     wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
+                EncSliceParameter::MPEG2(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
             },
 
             BufferType::EncMacroblockParameterBuffer(ref mut mb_param) => match mb_param {
@@ -283,6 +401,46 @@ impl Buffer {
                     wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
+                EncMiscParameter::ROI(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                EncMiscParameter::TemporalLayerStructure(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                EncMiscParameter::RIR(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                EncMiscParameter::AIR(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                EncMiscParameter::MultiPassFrameSize(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                EncMiscParameter::CustomRoundingControl(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                EncMiscParameter::SubMbPartPelH264(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                EncMiscParameter::EncQuality(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                EncMiscParameter::ParallelRateControl(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
+                EncMiscParameter::ExtensionDataSeqDisplayMPEG2(ref mut wrapper) => (
+                    wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of_val(wrapper.inner_mut()),
+                ),
             },
             BufferType::ProcPipelineParameter(ref mut proc_pipeline_param) => (
                 proc_pipeline_param.inner_mut() as *mut _ as *mut std::ffi::c_void,
@@ -294,16 +452,108 @@ impl Buffer {
                     std::mem::size_of_val(wrapper.inner_mut()),
                 ),
             },
+            BufferType::EncPackedHeaderParameter(ref mut wrapper) => (
+                wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                std::mem::size_of_val(wrapper.inner_mut()),
+            ),
+            BufferType::ContextParameterUpdate(ref mut wrapper) => (
+                wrapper.inner_mut() as *mut _ as *mut std::ffi::c_void,
+                std::mem::size_of_val(wrapper.inner_mut()),
+            ),
+            BufferType::EncPackedHeaderData(ref mut data) => {
+                (data.as_mut_ptr() as *mut std::ffi::c_void, data.len())
+            }
+            BufferType::BitPlane(ref mut wrapper) => (
+                wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                wrapper.inner_mut().len(),
+            ),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::Alf(ref mut wrapper) => (
+                wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                std::mem::size_of::<bindings::VAAlfDataVVC>(),
+            ),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::Lmcs(ref mut wrapper) => (
+                wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                std::mem::size_of::<bindings::VALmcsDataVVC>(),
+            ),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::SubPic(ref mut wrapper) => (
+                wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                std::mem::size_of::<bindings::VASubPicVVC>(),
+            ),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::Tile(ref mut wrapper) => (
+                wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                std::mem::size_of::<bindings::VATileBufferVVC>(),
+            ),
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::SliceStruct(ref mut wrapper) => (
+                wrapper.inner_mut().as_mut_ptr() as *mut std::ffi::c_void,
+                std::mem::size_of::<bindings::VASliceStructVVC>(),
+            ),
         };
 
-        // Safe because `self` represents a valid `VAContext`. `ptr` and `size` are also ensured to
-        // be correct, as `ptr` is just a cast to `*c_void` from a Rust struct, and `size` is
-        // computed from `std::mem::size_of_val`.
+        (ptr, size, nb_elements)
+    }
+
+    /// Creates a new `VASliceDataBufferType` buffer containing a copy of `data`. This is just a
+    /// helper for [`Context::create_slice_data`].
+    pub(crate) fn new_slice_data(context: Rc<Context>, data: &[u8]) -> Result<Self, VaError> {
+        // Safe because `data` is valid for `data.len()` bytes. libva only reads from it despite
+        // taking a mutable pointer.
+        unsafe {
+            Self::new_raw(
+                context,
+                bindings::VABufferType::VASliceDataBufferType,
+                data.len(),
+                1,
+                data.as_ptr() as *mut std::ffi::c_void,
+            )
+        }
+    }
+
+    /// Creates a new `VASliceDataBufferType` buffer of `size` bytes with unspecified content. This
+    /// is just a helper for [`Context::create_slice_data_uninit`].
+    pub(crate) fn new_slice_data_uninit(
+        context: Rc<Context>,
+        size: usize,
+    ) -> Result<Self, VaError> {
+        // Safe because a NULL pointer makes libva allocate the buffer without copying anything
+        // into it.
+        unsafe {
+            Self::new_raw(
+                context,
+                bindings::VABufferType::VASliceDataBufferType,
+                size,
+                1,
+                std::ptr::null_mut(),
+            )
+        }
+    }
+
+    /// Wraps a `vaCreateBuffer` call creating a buffer of `nb_elements` elements of `size` bytes,
+    /// initialized from `ptr` if it is not NULL.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must either be NULL or valid for reading `size * nb_elements` bytes.
+    unsafe fn new_raw(
+        context: Rc<Context>,
+        type_: bindings::VABufferType::Type,
+        size: usize,
+        nb_elements: usize,
+        ptr: *mut std::ffi::c_void,
+    ) -> Result<Self, VaError> {
+        let mut buffer_id = 0;
+
+        // Safe because `context` represents a valid `VAContext` and the caller guarantees that
+        // `ptr` is valid for the given size.
         va_check(unsafe {
             bindings::vaCreateBuffer(
                 context.display().handle(),
                 context.id(),
-                type_.inner(),
+                type_,
                 size as u32,
                 nb_elements as u32,
                 ptr,
@@ -314,9 +564,55 @@ impl Buffer {
         Ok(Self {
             context,
             id: buffer_id,
+            mapped: Cell::new(false),
+            backing: Cell::new(None),
         })
     }
 
+    /// Overwrites the content of this buffer with `type_`, so the buffer can be submitted again
+    /// without being recreated.
+    ///
+    /// `type_` must be of the same buffer type and size as the content the buffer has been created
+    /// with. The buffer must not be in use by a picture that is still being processed.
+    ///
+    /// This only requires a shared reference, so a buffer shared between pictures with
+    /// [`Picture::add_shared_buffer`](crate::Picture::add_shared_buffer) can be updated while
+    /// previous pictures still hold it. The update fails with [`MapBufferError::AlreadyMapped`] if
+    /// the buffer is currently mapped.
+    pub fn update(&self, mut type_: BufferType) -> Result<(), UpdateBufferError> {
+        let info = self.info()?;
+        if info.type_ != type_.inner() {
+            return Err(UpdateBufferError::TypeMismatch);
+        }
+
+        let (ptr, size, nb_elements) = Self::raw_parts(&mut type_);
+        if ptr.is_null() {
+            return Err(UpdateBufferError::NoContent);
+        }
+
+        let len = size * nb_elements;
+        let buffer_len = info.size as usize * info.num_elements as usize;
+        if len != buffer_len {
+            return Err(UpdateBufferError::SizeMismatch(buffer_len, len));
+        }
+
+        // Safe because `ptr` and `len` describe the content of `type_`, which is alive until the
+        // end of this function.
+        let content = unsafe { std::slice::from_raw_parts(ptr as *const u8, len) };
+        self.map_mut::<u8>()?[..len].copy_from_slice(content);
+
+        // The previous content is not referenced by the buffer anymore.
+        self.backing
+            .set(Some(type_).filter(BufferType::has_external_data));
+
+        Ok(())
+    }
+
+    /// Returns the ID of this buffer.
+    pub fn id(&self) -> bindings::VABufferID {
+        self.id
+    }
+
     /// Convenience function to return a `VABufferID` vector from a slice of `Buffer`s in order to
     /// easily interface with the C API where a buffer array might be needed.
     pub fn as_id_vec(buffers: &[Self]) -> Vec<bindings::VABufferID> {
@@ -343,6 +639,7 @@ pub enum BufferType {
     /// Abstraction over `VAPictureParameterBufferType`. Needed for MPEG2, VP8, VP9, H264, JPEGBaseline.
     PictureParameter(PictureParameter),
     /// Abstraction over `VASliceParameterBufferType`. Needed for MPEG2, VP8, VP9, H264, JPEGBaseline.
+    /// All the wrappers can hold several slices, which are then submitted as a single buffer.
     SliceParameter(SliceParameter),
     /// Abstraction over `VAIQMatrixBufferType`. Needed for VP8, H264, JPEGBaseline.
     IQMatrix(IQMatrix),
@@ -368,6 +665,29 @@ pub enum BufferType {
     ProcPipelineParameter(proc_pipeline::ProcPipelineParameterBuffer),
     /// Abstraction over `VAQMatrixBufferType`.
     QMatrix(QMatrix),
+    /// Abstraction over `VAEncPackedHeaderParameterBufferType`. Needed for H264, HEVC, AV1.
+    EncPackedHeaderParameter(EncPackedHeaderParameterBuffer),
+    /// Abstraction over `VAEncPackedHeaderDataBufferType`. Needed for H264, HEVC, AV1.
+    EncPackedHeaderData(Vec<u8>),
+    /// Abstraction over `VAContextParameterUpdateBufferType`.
+    ContextParameterUpdate(ContextParameterUpdateBuffer),
+    /// Abstraction over `VABitPlaneBufferType`. Needed for VC1.
+    BitPlane(vc1::BitPlaneBufferVC1),
+    /// Abstraction over `VAAlfBufferType`. Needed for VVC.
+    #[cfg(libva_1_22_or_higher)]
+    Alf(vvc::AlfBufferVVC),
+    /// Abstraction over `VALmcsBufferType`. Needed for VVC.
+    #[cfg(libva_1_22_or_higher)]
+    Lmcs(vvc::LmcsBufferVVC),
+    /// Abstraction over `VASubPicBufferType`. Needed for VVC.
+    #[cfg(libva_1_22_or_higher)]
+    SubPic(vvc::SubPicBufferVVC),
+    /// Abstraction over `VATileBufferType`. Needed for VVC.
+    #[cfg(libva_1_22_or_higher)]
+    Tile(vvc::TileBufferVVC),
+    /// Abstraction over `VASliceStructBufferType`. Needed for VVC.
+    #[cfg(libva_1_22_or_higher)]
+    SliceStruct(vvc::SliceStructBufferVVC),
 }
 
 impl BufferType {
@@ -405,8 +725,42 @@ impl BufferType {
                 bindings::VABufferType::VAProcPipelineParameterBufferType
             }
             BufferType::QMatrix(_) => bindings::VABufferType::VAQMatrixBufferType,
+            BufferType::EncPackedHeaderParameter(_) => {
+                bindings::VABufferType::VAEncPackedHeaderParameterBufferType
+            }
+            BufferType::EncPackedHeaderData(_) => {
+                bindings::VABufferType::VAEncPackedHeaderDataBufferType
+            }
+            BufferType::ContextParameterUpdate(_) => {
+                bindings::VABufferType::VAContextParameterUpdateBufferType
+            }
+            BufferType::BitPlane(_) => bindings::VABufferType::VABitPlaneBufferType,
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::Alf(_) => bindings::VABufferType::VAAlfBufferType,
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::Lmcs(_) => bindings::VABufferType::VALmcsBufferType,
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::SubPic(_) => bindings::VABufferType::VASubPicBufferType,
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::Tile(_) => bindings::VABufferType::VATileBufferType,
+            #[cfg(libva_1_22_or_higher)]
+            BufferType::SliceStruct(_) => bindings::VABufferType::VASliceStructBufferType,
         }
     }
+
+    /// Returns whether the data of this buffer type points to memory outside of it, like an array
+    /// of elements. Such memory is read by the driver when the buffer is rendered and must live
+    /// until the picture using the buffer has been processed.
+    fn has_external_data(&self) -> bool {
+        matches!(
+            self,
+            BufferType::EncMiscParameter(
+                EncMiscParameter::ROI(_)
+                    | EncMiscParameter::MultiPassFrameSize(_)
+                    | EncMiscParameter::ParallelRateControl(_)
+            )
+        )
+    }
 }
 
 /// Abstraction over the `PictureParameterBuffer` types we support.
@@ -431,6 +785,13 @@ pub enum PictureParameter {
     JPEGBaseline(jpeg_baseline::PictureParameterBufferJPEGBaseline),
     /// Wrapper over VAEncPictureParameterBufferJPEG
     EncJPEG(enc_jpeg::EncPictureParameterBufferJPEG),
+    /// Wrapper over VAPictureParameterBufferVC1
+    VC1(vc1::PictureParameterBufferVC1),
+    /// Wrapper over VAPictureParameterBufferMPEG4
+    MPEG4(mpeg4::PictureParameterBufferMPEG4),
+    /// Wrapper over VAPictureParameterBufferVVC
+    #[cfg(libva_1_22_or_higher)]
+    VVC(vvc::PictureParameterBufferVVC),
 }
 
 /// Abstraction over the `SliceParameterBuffer` types we support
@@ -453,6 +814,34 @@ pub enum SliceParameter {
     JPEGBaseline(jpeg_baseline::SliceParameterBufferJPEGBaseline),
     /// Wrapper over VAEncSliceParameterBufferJPEG
     EncJpeg(enc_jpeg::EncSliceParameterBufferJPEG),
+    /// Wrapper over VASliceParameterBufferVC1
+    VC1(vc1::SliceParameterBufferVC1),
+    /// Wrapper over VASliceParameterBufferMPEG4
+    MPEG4(mpeg4::SliceParameterBufferMPEG4),
+    /// Wrapper over an array of VASliceParameterBufferVVC
+    #[cfg(libva_1_22_or_higher)]
+    VVC(vvc::SliceParameterBufferVVC),
+}
+
+impl SliceParameter {
+    /// Returns the number of slices held by the wrapped buffer.
+    fn nb_elements(&self) -> usize {
+        match self {
+            SliceParameter::MPEG2(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::VP8(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::VP9(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::H264(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::HEVC(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::HEVCRext(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::AV1(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::JPEGBaseline(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::EncJpeg(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::VC1(ref wrapper) => wrapper.inner().len(),
+            SliceParameter::MPEG4(ref wrapper) => wrapper.inner().len(),
+            #[cfg(libva_1_22_or_higher)]
+            SliceParameter::VVC(ref wrapper) => wrapper.inner().len(),
+        }
+    }
 }
 
 /// Abstraction over the `IQMatrixBuffer` types we support.
@@ -467,6 +856,11 @@ pub enum IQMatrix {
     HEVC(hevc::IQMatrixBufferHEVC),
     /// Abstraction over `VAIQMatrixBufferJPEGBaseline``
     JPEGBaseline(jpeg_baseline::IQMatrixBufferJPEGBaseline),
+    /// Abstraction over `VAIQMatrixBufferMPEG4`
+    MPEG4(mpeg4::IQMatrixBufferMPEG4),
+    /// Abstraction over `VAScalingListVVC`
+    #[cfg(libva_1_22_or_higher)]
+    VVC(vvc::ScalingListVVC),
 }
 
 /// Abstraction over the `HuffmanTable` types we support.
@@ -493,6 +887,8 @@ pub enum EncSequenceParameter {
     VP9(vp9::EncSequenceParameterBufferVP9),
     /// Abstraction over `VAEncSequenceParameterBufferAV1`
     AV1(av1::EncSequenceParameterBufferAV1),
+    /// Abstraction over `VAEncSequenceParameterBufferMPEG2`
+    MPEG2(mpeg2::EncSequenceParameterBufferMPEG2),
 }
 
 /// Abstraction over the `EncPictureParameter` types we support.
@@ -507,6 +903,8 @@ pub enum EncPictureParameter {
     VP9(vp9::EncPictureParameterBufferVP9),
     /// Abstraction over `VAEncPictureParameterBufferAV1`
     AV1(av1::EncPictureParameterBufferAV1),
+    /// Abstraction over `VAEncPictureParameterBufferMPEG2`
+    MPEG2(mpeg2::EncPictureParameterBufferMPEG2),
 }
 
 /// Abstraction over the `EncSliceParameter` types we support.
@@ -517,6 +915,8 @@ pub enum EncSliceParameter {
     HEVC(hevc::EncSliceParameterBufferHEVC),
     /// Abstraction over `VAEncTileGroupBufferAV1`
     AV1(av1::EncTileGroupBufferAV1),
+    /// Abstraction over `VAEncSliceParameterBufferMPEG2`
+    MPEG2(mpeg2::EncSliceParameterBufferMPEG2),
 }
 
 /// Abstraction over the `EncMacroblockParameterBuffer` types we support.
@@ -543,6 +943,9 @@ impl EncCodedBuffer {
 }
 
 /// Helper to access a single segment of mapped coded buffer
+///
+/// `status` is the raw `VACodedBufferSegment` status, see [`MappedCodedSegment::decoded_status`]
+/// for its decoded form.
 pub struct MappedCodedSegment<'s> {
     pub bit_offset: u32,
     pub status: u32,
@@ -627,4 +1030,98 @@ pub enum EncMiscParameter {
     QualityLevel(EncMiscParameterBufferQualityLevel),
     /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterQuantization`.
     Quantization(EncMiscParameterQuantization),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterBufferROI`.
+    ROI(EncMiscParameterBufferROI),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterTemporalLayerStructure`.
+    TemporalLayerStructure(EncMiscParameterTemporalLayerStructure),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterRIR`.
+    RIR(EncMiscParameterRIR),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterAIR`.
+    AIR(EncMiscParameterAIR),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterBufferMultiPassFrameSize`.
+    MultiPassFrameSize(EncMiscParameterBufferMultiPassFrameSize),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterCustomRoundingControl`.
+    CustomRoundingControl(EncMiscParameterCustomRoundingControl),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterSubMbPartPelH264`.
+    SubMbPartPelH264(EncMiscParameterSubMbPartPelH264),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterEncQuality`.
+    EncQuality(EncMiscParameterEncQuality),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterParallelRateControl`.
+    ParallelRateControl(EncMiscParameterParallelRateControl),
+    /// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterExtensionDataSeqDisplayMPEG2`.
+    ExtensionDataSeqDisplayMPEG2(EncMiscParameterExtensionDataSeqDisplayMPEG2),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hevc_slice(slice_segment_address: u32) -> hevc::SliceParameterBufferHEVC {
+        let long_slice_flags = hevc::HevcLongSliceFlags::new(0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0);
+
+        hevc::SliceParameterBufferHEVC::new(
+            0,
+            0,
+            0,
+            0,
+            slice_segment_address,
+            [[0xff; 15]; 2],
+            &long_slice_flags,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            [0; 15],
+            [0; 15],
+            [[0; 2]; 15],
+            [[0; 2]; 15],
+            [0; 15],
+            [0; 15],
+            [[0; 2]; 15],
+            [[0; 2]; 15],
+            0,
+            0,
+            0,
+            0,
+        )
+    }
+
+    #[test]
+    fn batched_hevc_slices() {
+        let mut slices = hevc_slice(0);
+        slices.add_slice(hevc_slice(10));
+        slices.set_as_last();
+
+        let last_slice_of_pic = |slice: &bindings::VASliceParameterBufferHEVC| {
+            // Safe because all bit patterns are valid for this union of integers.
+            unsafe { slice.LongSliceFlags.fields.LastSliceOfPic() }
+        };
+        assert_eq!(slices.inner().len(), 2);
+        assert_eq!(slices.inner()[0].slice_segment_address, 0);
+        assert_eq!(last_slice_of_pic(&slices.inner()[0]), 0);
+        assert_eq!(slices.inner()[1].slice_segment_address, 10);
+        assert_eq!(last_slice_of_pic(&slices.inner()[1]), 1);
+
+        let mut type_ = BufferType::SliceParameter(SliceParameter::HEVC(slices));
+        let (ptr, size, nb_elements) = Buffer::raw_parts(&mut type_);
+        assert_eq!(nb_elements, 2);
+        assert_eq!(
+            size,
+            std::mem::size_of::<bindings::VASliceParameterBufferHEVC>()
+        );
+
+        let BufferType::SliceParameter(SliceParameter::HEVC(slices)) = &type_ else {
+            unreachable!()
+        };
+        assert_eq!(
+            ptr as *const bindings::VASliceParameterBufferHEVC,
+            slices.inner().as_ptr()
+        );
+    }
 }
@@ -556,9 +556,20 @@ impl SliceParameterBufferAV1 {
         });
     }
 
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
     pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferAV1> {
         self.0.as_mut()
     }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferAV1> {
+        &self.0
+    }
 }
 
 pub struct AV1EncSeqFields(bindings::_VAEncSequenceParameterBufferAV1__bindgen_ty_1);
@@ -0,0 +1,183 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Wrapper around buffers created with `vaCreateBuffer2`.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::bindings;
+use crate::buffer::Buffer;
+use crate::buffer::MapBufferError;
+use crate::buffer::MappedBufferMut;
+use crate::va_check;
+use crate::Context;
+use crate::VaError;
+
+/// Wrapper type representing a surface-shaped buffer created with `vaCreateBuffer2`, e.g. a QP or
+/// motion vector predictor map.
+///
+/// Such a buffer is made of `height` rows of `width` units of `unit_size` bytes each, and its rows
+/// are separated by `pitch` bytes, as chosen by the driver.
+pub struct Buffer2D {
+    buffer: Buffer,
+    width: u32,
+    height: u32,
+    unit_size: u32,
+    pitch: u32,
+}
+
+impl Buffer2D {
+    /// Creates a new 2D buffer of `width`x`height` units by wrapping a `vaCreateBuffer2` call.
+    /// This is just a helper for [`Context::create_buffer_2d`].
+    pub(crate) fn new(
+        context: Rc<Context>,
+        type_: bindings::VABufferType::Type,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, VaError> {
+        let mut unit_size = 0;
+        let mut pitch = 0;
+        let mut buffer_id = 0;
+
+        // Safe because `context` represents a valid `VAContext`.
+        va_check(unsafe {
+            bindings::vaCreateBuffer2(
+                context.display().handle(),
+                context.id(),
+                type_,
+                width,
+                height,
+                &mut unit_size,
+                &mut pitch,
+                &mut buffer_id,
+            )
+        })?;
+
+        Ok(Self {
+            buffer: Buffer {
+                context,
+                id: buffer_id,
+                mapped: Cell::new(false),
+                backing: Cell::new(None),
+            },
+            width,
+            height,
+            unit_size,
+            pitch,
+        })
+    }
+
+    /// Returns the width of the buffer, in units.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the buffer, in rows.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the size of a unit, in bytes, as returned by the driver.
+    pub fn unit_size(&self) -> u32 {
+        self.unit_size
+    }
+
+    /// Returns the distance between two rows, in bytes, as returned by the driver.
+    pub fn pitch(&self) -> u32 {
+        self.pitch
+    }
+
+    /// Returns the underlying buffer.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Consumes the 2D buffer and returns the underlying buffer, e.g. to add it to a
+    /// [`crate::Picture`].
+    pub fn into_buffer(self) -> Buffer {
+        self.buffer
+    }
+
+    /// Maps the buffer and returns a view of its rows that respects the pitch.
+    pub fn map(&mut self) -> Result<MappedBuffer2D, MapBufferError> {
+        let row_len = self.width as usize * self.unit_size as usize;
+        let pitch = self.pitch as usize;
+        let height = self.height as usize;
+        if pitch < row_len || pitch == 0 {
+            return Err(MapBufferError::InvalidPitch);
+        }
+
+        // Safe because the driver allocates `pitch` bytes for each of the `height` rows of the
+        // buffer.
+        let mapping = unsafe { self.buffer.map_mut_with_len(pitch * height)? };
+
+        Ok(MappedBuffer2D {
+            mapping,
+            row_len,
+            pitch,
+            height,
+        })
+    }
+}
+
+impl AsRef<Buffer> for Buffer2D {
+    fn as_ref(&self) -> &Buffer {
+        &self.buffer
+    }
+}
+
+/// Mapping of a [`Buffer2D`], giving access to its rows.
+///
+/// The buffer is unmapped when this object is dropped.
+pub struct MappedBuffer2D<'a> {
+    mapping: MappedBufferMut<'a, u8>,
+    /// Length of the useful part of a row, in bytes.
+    row_len: usize,
+    pitch: usize,
+    height: usize,
+}
+
+impl<'a> MappedBuffer2D<'a> {
+    /// Returns the number of rows of the buffer.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns row `y` of the buffer, without the padding bytes at its end, or `None` if `y` is
+    /// out of bounds.
+    pub fn row(&self, y: usize) -> Option<&[u8]> {
+        match y {
+            y if y < self.height => Some(&self.mapping[y * self.pitch..][..self.row_len]),
+            _ => None,
+        }
+    }
+
+    /// Returns row `y` of the buffer for writing, without the padding bytes at its end, or `None`
+    /// if `y` is out of bounds.
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [u8]> {
+        match y {
+            y if y < self.height => Some(&mut self.mapping[y * self.pitch..][..self.row_len]),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the rows of the buffer.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        self.mapping
+            .chunks(self.pitch)
+            .take(self.height)
+            .map(|row| &row[..self.row_len])
+    }
+
+    /// Returns an iterator over the rows of the buffer for writing.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u8]> {
+        let row_len = self.row_len;
+
+        self.mapping
+            .chunks_mut(self.pitch)
+            .take(self.height)
+            .map(move |row| &mut row[..row_len])
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Assembly of the segments of a coded buffer into a contiguous bitstream, and coded buffer size
+//! estimation.
+
+use std::io;
+use std::io::Write;
+
+use crate::bindings;
+use crate::buffer::EncCodedBuffer;
+use crate::buffer::MappedCodedBuffer;
+
+/// Margin added to coded buffer size estimates for headers (parameter sets, SEI, etc.).
+const HEADER_MARGIN: usize = 16 * 1024;
+
+/// Ratio of the worst-case size of an encoded frame over its NV12 size, for the codecs we know
+/// about.
+fn worst_case_ratio(profile: bindings::VAProfile::Type) -> (usize, usize) {
+    match profile {
+        bindings::VAProfile::VAProfileJPEGBaseline => (3, 2),
+        bindings::VAProfile::VAProfileHEVCMain
+        | bindings::VAProfile::VAProfileHEVCMain10
+        | bindings::VAProfile::VAProfileVP9Profile0
+        | bindings::VAProfile::VAProfileVP9Profile1
+        | bindings::VAProfile::VAProfileVP9Profile2
+        | bindings::VAProfile::VAProfileVP9Profile3
+        | bindings::VAProfile::VAProfileAV1Profile0
+        | bindings::VAProfile::VAProfileAV1Profile1 => (3, 4),
+        _ => (1, 1),
+    }
+}
+
+impl EncCodedBuffer {
+    /// Returns an estimate of the size of a coded buffer large enough to contain any frame of a
+    /// `width`x`height` stream encoded with `profile`, at `bitrate` bits per second and
+    /// `framerate` frames per second. `bitrate` may be zero for constant QP encoding, in which
+    /// case the worst case for the resolution is returned.
+    ///
+    /// The estimate is meant to be passed to [`crate::Context::create_enc_coded`]. It leaves
+    /// enough room for key frames to be several times larger than the average frame, but a driver
+    /// may still overflow it in pathological cases, see
+    /// [`EncCodedBuffer::encode_with_overflow_retry`].
+    pub fn estimate_size(
+        profile: bindings::VAProfile::Type,
+        width: u32,
+        height: u32,
+        bitrate: u64,
+        framerate: u32,
+    ) -> usize {
+        // Key frames can be this many times larger than the average frame.
+        const KEY_FRAME_HEADROOM: u64 = 8;
+
+        let (num, den) = worst_case_ratio(profile);
+        let raw_size = width as usize * height as usize * 3 / 2;
+        let worst_case = raw_size * num / den;
+
+        let size = match bitrate {
+            0 => worst_case,
+            bitrate => {
+                let frame_size = bitrate / 8 / u64::from(std::cmp::max(framerate, 1));
+                let frame_size = usize::try_from(frame_size.saturating_mul(KEY_FRAME_HEADROOM))
+                    .unwrap_or(usize::MAX);
+
+                frame_size.clamp(worst_case / 8, worst_case)
+            }
+        };
+
+        size + HEADER_MARGIN
+    }
+}
+
+/// Writes a sequence of bits, most significant bit first, into a writer.
+struct BitWriter<W: Write> {
+    writer: W,
+    /// Bits not written yet, in the `nbits` least significant bits.
+    partial: u8,
+    nbits: u32,
+    /// Bytes waiting to be written.
+    pending: Vec<u8>,
+}
+
+impl<W: Write> BitWriter<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            partial: 0,
+            nbits: 0,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Appends the content of `data` starting at bit `bit_offset`.
+    fn push(&mut self, data: &[u8], bit_offset: u32) -> io::Result<()> {
+        let data = data.get(bit_offset as usize / 8..).unwrap_or_default();
+        let skip = bit_offset % 8;
+
+        let Some((&first, rest)) = data.split_first() else {
+            return Ok(());
+        };
+
+        if self.nbits == 0 && skip == 0 {
+            return self.writer.write_all(data);
+        }
+
+        self.push_bits(first & (0xff >> skip), 8 - skip);
+        for &byte in rest {
+            self.push_bits(byte, 8);
+        }
+
+        self.writer.write_all(&self.pending)?;
+        self.pending.clear();
+
+        Ok(())
+    }
+
+    /// Appends the `count` least significant bits of `value`.
+    fn push_bits(&mut self, value: u8, count: u32) {
+        let acc = (u16::from(self.partial) << count) | u16::from(value);
+        let total = self.nbits + count;
+
+        if total >= 8 {
+            self.pending.push((acc >> (total - 8)) as u8);
+            self.nbits = total - 8;
+        } else {
+            self.nbits = total;
+        }
+
+        self.partial = (acc & ((1 << self.nbits) - 1)) as u8;
+    }
+
+    /// Writes the remaining bits, padded with zeroes to a full byte.
+    fn finish(mut self) -> io::Result<()> {
+        if self.nbits > 0 {
+            let last = self.partial << (8 - self.nbits);
+            self.writer.write_all(&[last])?;
+        }
+
+        self.writer.flush()
+    }
+}
+
+/// Writes the bitstream content of `segments`, given as `(data, bit_offset)` pairs, into
+/// `writer`.
+fn assemble<'a, W: Write>(
+    segments: impl Iterator<Item = (&'a [u8], u32)>,
+    writer: W,
+) -> io::Result<()> {
+    let mut bit_writer = BitWriter::new(writer);
+
+    for (data, bit_offset) in segments {
+        bit_writer.push(data, bit_offset)?;
+    }
+
+    bit_writer.finish()
+}
+
+impl<'p> MappedCodedBuffer<'p> {
+    /// Returns the segments that contain bitstream data, as `(data, bit_offset)` pairs.
+    fn bitstream_segments(&self) -> impl Iterator<Item = (&[u8], u32)> {
+        self.iter()
+            .filter(|segment| !segment.is_codec_specific())
+            .map(|segment| (segment.buf, segment.bit_offset))
+    }
+
+    /// Returns the size in bytes of the bitstream contained in the coded buffer, i.e. the number
+    /// of bytes written by [`MappedCodedBuffer::write_to`].
+    pub fn size(&self) -> usize {
+        let bits: usize = self
+            .bitstream_segments()
+            .map(|(data, bit_offset)| (data.len() * 8).saturating_sub(bit_offset as usize))
+            .sum();
+
+        bits.div_ceil(8)
+    }
+
+    /// Writes the bitstream contained in the coded buffer into `writer`.
+    ///
+    /// The segments are concatenated in order, skipping the first `bit_offset` bits of each of
+    /// them. Segments holding codec-specific structures instead of bitstream data are left out.
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        assemble(self.bitstream_segments(), writer)
+    }
+
+    /// Returns the bitstream contained in the coded buffer as a contiguous vector. See
+    /// [`MappedCodedBuffer::write_to`].
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.size());
+        // Writing into a `Vec` cannot fail.
+        let _ = self.write_to(&mut data);
+
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assemble_vec(segments: &[(&[u8], u32)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        assemble(segments.iter().copied(), &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn assemble_aligned() {
+        let data = assemble_vec(&[(&[0x00, 0x00, 0x01], 0), (&[0xaa, 0x65, 0xb8], 8)]);
+        assert_eq!(data, [0x00, 0x00, 0x01, 0x65, 0xb8]);
+    }
+
+    #[test]
+    fn assemble_unaligned() {
+        // 0b1111_0000 from bit 4 gives 0000, followed by 0b1010_1010 from bit 0.
+        let data = assemble_vec(&[(&[0xf0], 4), (&[0xaa], 0)]);
+        assert_eq!(data, [0b0000_1010, 0b1010_0000]);
+
+        // Everything is skipped in the first segment.
+        let data = assemble_vec(&[(&[0xff], 8), (&[0x12, 0x34], 4)]);
+        assert_eq!(data, [0x23, 0x40]);
+    }
+
+    #[test]
+    fn estimate_size() {
+        let cqp = EncCodedBuffer::estimate_size(
+            bindings::VAProfile::VAProfileH264Main,
+            1920,
+            1080,
+            0,
+            30,
+        );
+        assert_eq!(cqp, 1920 * 1080 * 3 / 2 + HEADER_MARGIN);
+
+        let vbr = EncCodedBuffer::estimate_size(
+            bindings::VAProfile::VAProfileH264Main,
+            1920,
+            1080,
+            20_000_000,
+            30,
+        );
+        assert_eq!(vbr, 20_000_000 / 8 / 30 * 8 + HEADER_MARGIN);
+    }
+}
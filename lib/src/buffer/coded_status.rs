@@ -0,0 +1,211 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Decoding of the status of coded buffer segments and recovery from coded buffer overflows.
+
+use std::rc::Rc;
+
+use thiserror::Error;
+
+use crate::bindings;
+use crate::buffer::EncCodedBuffer;
+use crate::buffer::MappedCodedBuffer;
+use crate::buffer::MappedCodedSegment;
+use crate::Context;
+use crate::VaError;
+
+/// Extracts the field covered by `mask` from `status`.
+fn field(status: u32, mask: u32) -> u32 {
+    (status & mask) >> mask.trailing_zeros()
+}
+
+/// Decoded `status` of a `VACodedBufferSegment`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CodedSegmentStatus {
+    /// Average QP of the picture.
+    pub average_qp: u8,
+    /// The segment contains a slice larger than the maximum slice size.
+    pub large_slice: bool,
+    /// The coded buffer was too small to contain all the slices of the picture.
+    pub slice_overflow: bool,
+    /// The bitrate is higher than the target bitrate.
+    pub bitrate_overflow: bool,
+    /// The bitrate is higher than the maximum bitrate.
+    pub bitrate_high: bool,
+    /// The frame is larger than the maximum frame size.
+    pub frame_size_overflow: bool,
+    /// The produced bitstream is corrupted.
+    pub bad_bitstream: bool,
+    /// Number of macroblocks above the adaptive intra refresh threshold.
+    pub air_mb_over_threshold: u8,
+    /// Number of passes used by the encoder to encode the frame.
+    pub number_passes: u8,
+    /// The segment contains a codec-specific structure instead of bitstream data.
+    pub codec_specific_structure: bool,
+}
+
+impl From<u32> for CodedSegmentStatus {
+    fn from(status: u32) -> Self {
+        Self {
+            average_qp: field(status, bindings::VA_CODED_BUF_STATUS_PICTURE_AVE_QP_MASK) as u8,
+            large_slice: status & bindings::VA_CODED_BUF_STATUS_LARGE_SLICE_MASK != 0,
+            slice_overflow: status & bindings::VA_CODED_BUF_STATUS_SLICE_OVERFLOW_MASK != 0,
+            bitrate_overflow: status & bindings::VA_CODED_BUF_STATUS_BITRATE_OVERFLOW != 0,
+            bitrate_high: status & bindings::VA_CODED_BUF_STATUS_BITRATE_HIGH != 0,
+            frame_size_overflow: status & bindings::VA_CODED_BUF_STATUS_FRAME_SIZE_OVERFLOW != 0,
+            bad_bitstream: status & bindings::VA_CODED_BUF_STATUS_BAD_BITSTREAM != 0,
+            air_mb_over_threshold: field(
+                status,
+                bindings::VA_CODED_BUF_STATUS_AIR_MB_OVER_THRESHOLD,
+            ) as u8,
+            number_passes: field(status, bindings::VA_CODED_BUF_STATUS_NUMBER_PASSES_MASK) as u8,
+            codec_specific_structure: status
+                & bindings::VA_CODED_BUF_STATUS_CODEC_SPECIFIC_STRUCTURE
+                != 0,
+        }
+    }
+}
+
+/// Codec-specific structure returned by the driver in its own coded buffer segment.
+#[derive(Debug, Clone, Copy)]
+pub enum CodedSegmentPayload<'a> {
+    /// `VACodedBufferVP9Status`, returned when encoding VP9.
+    VP9(bindings::VACodedBufferVP9Status),
+    /// Structure we cannot interpret, e.g. when encoding AV1 for which libva does not define a
+    /// status structure.
+    Other(&'a [u8]),
+}
+
+impl<'s> MappedCodedSegment<'s> {
+    /// Returns the decoded status of this segment.
+    pub fn decoded_status(&self) -> CodedSegmentStatus {
+        CodedSegmentStatus::from(self.status)
+    }
+
+    /// Returns whether this segment contains a codec-specific structure instead of bitstream
+    /// data.
+    pub fn is_codec_specific(&self) -> bool {
+        self.status & bindings::VA_CODED_BUF_STATUS_CODEC_SPECIFIC_STRUCTURE != 0
+    }
+
+    /// Returns the codec-specific structure contained in this segment, or `None` if the segment
+    /// contains bitstream data.
+    ///
+    /// `profile` is the profile the stream is encoded with, and is used to interpret the content
+    /// of the segment.
+    pub fn codec_specific_payload(
+        &self,
+        profile: bindings::VAProfile::Type,
+    ) -> Option<CodedSegmentPayload<'s>> {
+        if !self.is_codec_specific() {
+            return None;
+        }
+
+        let is_vp9 = matches!(
+            profile,
+            bindings::VAProfile::VAProfileVP9Profile0
+                | bindings::VAProfile::VAProfileVP9Profile1
+                | bindings::VAProfile::VAProfileVP9Profile2
+                | bindings::VAProfile::VAProfileVP9Profile3
+        );
+
+        if is_vp9 && self.buf.len() >= std::mem::size_of::<bindings::VACodedBufferVP9Status>() {
+            // Safe because we checked that `buf` is large enough to contain the structure, which
+            // is made of integers only and thus valid for any bit pattern. The driver gives no
+            // alignment guarantee, hence the unaligned read.
+            let status = unsafe {
+                std::ptr::read_unaligned(
+                    self.buf.as_ptr() as *const bindings::VACodedBufferVP9Status
+                )
+            };
+
+            Some(CodedSegmentPayload::VP9(status))
+        } else {
+            Some(CodedSegmentPayload::Other(self.buf))
+        }
+    }
+}
+
+impl<'p> MappedCodedBuffer<'p> {
+    /// Returns whether the coded buffer was too small to contain the whole encoded picture, in
+    /// which case the picture must be encoded again into a larger buffer.
+    pub fn slice_overflow(&self) -> bool {
+        self.iter()
+            .any(|segment| segment.status & bindings::VA_CODED_BUF_STATUS_SLICE_OVERFLOW_MASK != 0)
+    }
+}
+
+/// Error type for [`EncCodedBuffer::encode_with_overflow_retry`].
+#[derive(Debug, Error)]
+pub enum EncodeRetryError<E> {
+    #[error("error while allocating or mapping the coded buffer: {0}")]
+    VaError(#[from] VaError),
+    #[error("error while encoding the picture: {0}")]
+    Encode(E),
+    #[error("the coded buffer overflowed with the maximum size of {0} bytes")]
+    Overflow(usize),
+}
+
+impl EncCodedBuffer {
+    /// Encodes a picture into a newly allocated coded buffer of `initial_size` bytes, and encodes
+    /// it again into a buffer twice as large as long as the driver reports a slice overflow, up to
+    /// `max_size` bytes.
+    ///
+    /// `encode` is responsible for submitting the picture with the coded buffer it is given and
+    /// waiting for its completion. It is called again with the new coded buffer after each
+    /// overflow. On success, the coded buffer holding the whole picture is returned along with
+    /// the value returned by the last call to `encode`.
+    pub fn encode_with_overflow_retry<F, R, E>(
+        context: &Rc<Context>,
+        initial_size: usize,
+        max_size: usize,
+        mut encode: F,
+    ) -> Result<(EncCodedBuffer, R), EncodeRetryError<E>>
+    where
+        F: FnMut(&EncCodedBuffer) -> Result<R, E>,
+    {
+        let mut size = std::cmp::max(initial_size, 1);
+
+        loop {
+            let coded_buffer = context.create_enc_coded(size)?;
+            let res = encode(&coded_buffer).map_err(EncodeRetryError::Encode)?;
+
+            if !MappedCodedBuffer::new(&coded_buffer)?.slice_overflow() {
+                return Ok((coded_buffer, res));
+            }
+
+            if size >= max_size {
+                return Err(EncodeRetryError::Overflow(size));
+            }
+
+            size = std::cmp::min(size.saturating_mul(2), max_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_status() {
+        let status = CodedSegmentStatus::from(0x8300_9a1e);
+
+        assert_eq!(
+            status,
+            CodedSegmentStatus {
+                average_qp: 0x1e,
+                large_slice: false,
+                slice_overflow: true,
+                bitrate_overflow: false,
+                bitrate_high: true,
+                frame_size_overflow: true,
+                bad_bitstream: true,
+                air_mb_over_threshold: 0,
+                number_passes: 3,
+                codec_specific_structure: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Wrappers around context priority and `VAContextParameterUpdateBuffer`.
+
+use thiserror::Error;
+
+use crate::bindings;
+
+/// Context priority capabilities of a driver, as reported by the `VAConfigAttribContextPriority`
+/// attribute.
+///
+/// Priorities range from 0 to `max_priority`, higher values meaning higher priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextPriorityCaps {
+    /// Highest priority supported.
+    pub max_priority: u16,
+}
+
+/// Error returned when a context priority is not supported by the driver.
+#[derive(Debug, Error)]
+pub enum ContextPriorityError {
+    #[error("priority {0} is above the maximum supported priority {1}")]
+    OutOfRange(u16, u16),
+}
+
+/// Builds a `VAConfigAttribValContextPriority` holding `priority`.
+fn context_priority_val(priority: u16) -> bindings::VAConfigAttribValContextPriority {
+    let _bitfield_1 = bindings::_VAConfigAttribValContextPriority__bindgen_ty_1::new_bitfield_1(
+        u32::from(priority),
+        Default::default(),
+    );
+
+    bindings::VAConfigAttribValContextPriority {
+        bits: bindings::_VAConfigAttribValContextPriority__bindgen_ty_1 {
+            _bitfield_align_1: Default::default(),
+            _bitfield_1,
+        },
+    }
+}
+
+impl ContextPriorityCaps {
+    /// Decodes the value of the `VAConfigAttribContextPriority` attribute.
+    pub fn from_attribute(value: u32) -> Self {
+        let attrib = bindings::VAConfigAttribValContextPriority { value };
+
+        // Safe because all bit patterns are valid for this union of integers.
+        let bits = unsafe { attrib.bits };
+
+        Self {
+            max_priority: bits.priority() as u16,
+        }
+    }
+
+    /// Checks that `priority` is supported.
+    pub fn validate(&self, priority: u16) -> Result<(), ContextPriorityError> {
+        if priority > self.max_priority {
+            return Err(ContextPriorityError::OutOfRange(
+                priority,
+                self.max_priority,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the `VAConfigAttribContextPriority` attribute to pass to
+    /// [`crate::Display::create_config`] in order for contexts created from the config to run
+    /// with `priority`.
+    pub fn config_attrib(
+        &self,
+        priority: u16,
+    ) -> Result<bindings::VAConfigAttrib, ContextPriorityError> {
+        self.validate(priority)?;
+
+        // Safe because all bit patterns are valid for this union of integers.
+        let value = unsafe { context_priority_val(priority).value };
+
+        Ok(bindings::VAConfigAttrib {
+            type_: bindings::VAConfigAttribType::VAConfigAttribContextPriority,
+            value,
+        })
+    }
+}
+
+/// Wrapper over the `VAContextParameterUpdateBuffer` FFI type, used to change the parameters of a
+/// running context.
+///
+/// The update takes effect when the buffer is rendered as part of a picture.
+pub struct ContextParameterUpdateBuffer(Box<bindings::VAContextParameterUpdateBuffer>);
+
+impl ContextParameterUpdateBuffer {
+    /// Creates the wrapper. `context_priority` is the new priority of the context, or `None` to
+    /// leave it unchanged.
+    pub fn new(context_priority: Option<u16>) -> Self {
+        let _bitfield_1 =
+            bindings::_VAContextParameterUpdateBuffer__bindgen_ty_1__bindgen_ty_1::new_bitfield_1(
+                context_priority.is_some() as u32,
+                Default::default(),
+            );
+
+        Self(Box::new(bindings::VAContextParameterUpdateBuffer {
+            flags: bindings::_VAContextParameterUpdateBuffer__bindgen_ty_1 {
+                bits: bindings::_VAContextParameterUpdateBuffer__bindgen_ty_1__bindgen_ty_1 {
+                    _bitfield_align_1: Default::default(),
+                    _bitfield_1,
+                },
+            },
+            context_priority: context_priority_val(context_priority.unwrap_or(0)),
+            ..Default::default()
+        }))
+    }
+
+    /// Creates an update changing only the priority of the context, checking that `priority` is
+    /// supported according to `caps`.
+    pub fn with_priority(
+        caps: &ContextPriorityCaps,
+        priority: u16,
+    ) -> Result<Self, ContextPriorityError> {
+        caps.validate(priority)?;
+
+        Ok(Self::new(Some(priority)))
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAContextParameterUpdateBuffer {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAContextParameterUpdateBuffer {
+        self.0.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_priority_round_trip() {
+        let caps = ContextPriorityCaps::from_attribute(0xffff_0007);
+        assert_eq!(caps.max_priority, 7);
+
+        let attrib = caps.config_attrib(5).unwrap();
+        assert_eq!(
+            attrib.type_,
+            bindings::VAConfigAttribType::VAConfigAttribContextPriority
+        );
+        assert_eq!(attrib.value, 5);
+        assert_eq!(
+            ContextPriorityCaps::from_attribute(attrib.value).max_priority,
+            5
+        );
+
+        assert!(matches!(
+            caps.config_attrib(8),
+            Err(ContextPriorityError::OutOfRange(8, 7))
+        ));
+        assert!(matches!(
+            ContextParameterUpdateBuffer::with_priority(&caps, 8),
+            Err(ContextPriorityError::OutOfRange(8, 7))
+        ));
+    }
+
+    #[test]
+    fn context_parameter_update_flags() {
+        let caps = ContextPriorityCaps { max_priority: 7 };
+
+        let update = ContextParameterUpdateBuffer::with_priority(&caps, 7).unwrap();
+        // Safe because all bit patterns are valid for these unions of integers.
+        let (flags, priority) = unsafe {
+            (
+                update.inner().flags.bits,
+                update.inner().context_priority.bits,
+            )
+        };
+        assert_eq!(flags.context_priority_update(), 1);
+        assert_eq!(priority.priority(), 7);
+
+        let update = ContextParameterUpdateBuffer::new(None);
+        // Safe because all bit patterns are valid for this union of integers.
+        let flags = unsafe { update.inner().flags.value };
+        assert_eq!(flags, 0);
+    }
+}
@@ -101,7 +101,7 @@ impl EncSliceParameterBufferJPEGComponent {
 }
 
 /// Wrapper over the `VAEncSliceParameterBufferJPEG` FFI type.
-pub struct EncSliceParameterBufferJPEG(Box<bindings::VAEncSliceParameterBufferJPEG>);
+pub struct EncSliceParameterBufferJPEG(Vec<bindings::VAEncSliceParameterBufferJPEG>);
 
 impl EncSliceParameterBufferJPEG {
     /// Creates the wrapper.
@@ -110,21 +110,27 @@ impl EncSliceParameterBufferJPEG {
         num_components: u16,
         components: [EncSliceParameterBufferJPEGComponent; 4usize],
     ) -> Self {
-        Self(Box::new(bindings::VAEncSliceParameterBufferJPEG {
+        Self(vec![bindings::VAEncSliceParameterBufferJPEG {
             restart_interval,
             num_components,
             components: components.map(|component| component.0),
             va_reserved: Default::default(),
-        }))
+        }])
     }
 
-    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAEncSliceParameterBufferJPEG {
-        self.0.as_mut()
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VAEncSliceParameterBufferJPEG> {
+        &mut self.0
     }
 
     /// Returns the inner FFI type. Useful for testing purposes.
-    pub fn inner(&self) -> &bindings::VAEncSliceParameterBufferJPEG {
-        self.0.as_ref()
+    pub fn inner(&self) -> &Vec<bindings::VAEncSliceParameterBufferJPEG> {
+        &self.0
     }
 }
 
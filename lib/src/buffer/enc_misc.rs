@@ -4,9 +4,17 @@
 
 //! Wrappers around `VAEncMisc*` types.
 
+use bitflags::bitflags;
+use thiserror::Error;
+
 use crate::bindings;
 
-#[repr(C)]
+/// A `VAEncMiscParameterBuffer` header immediately followed by its payload of type `T`.
+///
+/// libva reads the payload at the `data` member of the header, i.e. right after its 4 bytes. The
+/// struct is packed so the payload stays at that offset even when `T` contains pointers and is
+/// more aligned than the header.
+#[repr(C, packed(4))]
 #[derive(Default)]
 pub struct MiscEncParamBuffer<T> {
     hdr: bindings::VAEncMiscParameterBuffer,
@@ -57,6 +65,11 @@ impl EncMiscParameterFrameRate {
         ))
     }
 
+    /// Sets the frame rate.
+    pub fn set_framerate(&mut self, framerate: u32) {
+        self.0.value.framerate = framerate;
+    }
+
     pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterFrameRate> {
         &self.0
     }
@@ -144,6 +157,33 @@ impl EncMiscParameterRateControl {
         ))
     }
 
+    /// Sets the target bitrate.
+    pub fn set_bits_per_second(&mut self, bits_per_second: u32) {
+        self.0.value.bits_per_second = bits_per_second;
+    }
+
+    /// Sets the target bitrate as a percentage of the maximum bitrate.
+    pub fn set_target_percentage(&mut self, target_percentage: u32) {
+        self.0.value.target_percentage = target_percentage;
+    }
+
+    /// Sets the initial, minimum and maximum QP.
+    pub fn set_qp(&mut self, initial_qp: u32, min_qp: u32, max_qp: u32) {
+        self.0.value.initial_qp = initial_qp;
+        self.0.value.min_qp = min_qp;
+        self.0.value.max_qp = max_qp;
+    }
+
+    /// Sets the rate control flags.
+    pub fn set_rc_flags(&mut self, rc_flags: RcFlags) {
+        self.0.value.rc_flags = rc_flags.0;
+    }
+
+    /// Sets the target frame size.
+    pub fn set_target_frame_size(&mut self, target_frame_size: u32) {
+        self.0.value.target_frame_size = target_frame_size;
+    }
+
     pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterRateControl> {
         &self.0
     }
@@ -335,3 +375,906 @@ impl EncMiscParameterQuantization {
         &mut self.0
     }
 }
+
+/// Region-of-interest capabilities of an encoder, as reported by the `VAConfigAttribEncROI`
+/// attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncROICaps {
+    /// Maximum number of regions supported.
+    pub num_roi_regions: u32,
+    /// Whether priority-based regions are supported when bitrate control is enabled.
+    pub roi_rc_priority_support: bool,
+    /// Whether QP delta regions are supported when bitrate control is enabled.
+    pub roi_rc_qp_delta_support: bool,
+}
+
+impl EncROICaps {
+    /// Decodes the value of the `VAConfigAttribEncROI` attribute.
+    pub fn from_attribute(value: u32) -> Self {
+        let attrib = bindings::VAConfigAttribValEncROI { value };
+
+        // Safe because all bit patterns are valid for this union of integers.
+        let bits = unsafe { attrib.bits };
+
+        Self {
+            num_roi_regions: bits.num_roi_regions(),
+            roi_rc_priority_support: bits.roi_rc_priority_support() != 0,
+            roi_rc_qp_delta_support: bits.roi_rc_qp_delta_support() != 0,
+        }
+    }
+}
+
+/// Error returned when a ROI parameter is not supported by the encoder.
+#[derive(Debug, Error)]
+pub enum EncROIError {
+    #[error("{0} regions requested, but the encoder supports at most {1}")]
+    TooManyRegions(usize, u32),
+    #[error("priority-based regions are not supported with bitrate control")]
+    PriorityNotSupported,
+    #[error("QP delta regions are not supported with bitrate control")]
+    QpDeltaNotSupported,
+    #[error("QP delta {0} is outside of the [{1}, {2}] range")]
+    QpDeltaOutOfRange(i8, i8, i8),
+}
+
+/// How the value of the regions of a [`EncMiscParameterBufferROI`] is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncROIMode {
+    /// The value is a QP delta applied to the region. Negative values improve quality.
+    QpDelta,
+    /// The value is a priority used by the bitrate control. Higher values improve quality.
+    Priority,
+}
+
+/// Wrapper over the `VAEncROI` FFI type.
+pub struct EncROI(bindings::VAEncROI);
+
+impl EncROI {
+    /// Creates a region covering `roi_rectangle`, with `roi_value` interpreted according to the
+    /// [`EncROIMode`] of the buffer.
+    pub fn new(roi_rectangle: bindings::VARectangle, roi_value: i8) -> Self {
+        Self(bindings::VAEncROI {
+            roi_rectangle,
+            roi_value,
+        })
+    }
+}
+
+/// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterBufferROI`, owning the array of
+/// regions it points to.
+///
+/// The driver reads the regions through a pointer when the buffer is rendered. The
+/// [`Buffer`](crate::Buffer) created from this parameter therefore keeps the wrapper alive until
+/// the buffer is destroyed, which must not happen before `vaEndPicture` returns for the picture
+/// using it.
+pub struct EncMiscParameterBufferROI {
+    buffer: Box<MiscEncParamBuffer<bindings::VAEncMiscParameterBufferROI>>,
+    rois: Vec<bindings::VAEncROI>,
+}
+
+impl EncMiscParameterBufferROI {
+    /// Creates the wrapper.
+    ///
+    /// `min_delta_qp` and `max_delta_qp` bound the QP delta the driver may apply to any region.
+    pub fn new(rois: Vec<EncROI>, mode: EncROIMode, min_delta_qp: i8, max_delta_qp: i8) -> Self {
+        let mut rois: Vec<_> = rois.into_iter().map(|roi| roi.0).collect();
+
+        let _bitfield_1 =
+            bindings::_VAEncMiscParameterBufferROI__bindgen_ty_1__bindgen_ty_1::new_bitfield_1(
+                (mode == EncROIMode::QpDelta) as u32,
+                Default::default(),
+            );
+
+        let buffer = MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeROI,
+            bindings::VAEncMiscParameterBufferROI {
+                num_roi: rois.len() as u32,
+                max_delta_qp,
+                min_delta_qp,
+                // The vector's heap storage does not move when `Self` does, and `Buffer` keeps
+                // `Self` alive for as long as the VA buffer exists.
+                roi: rois.as_mut_ptr(),
+                roi_flags: bindings::_VAEncMiscParameterBufferROI__bindgen_ty_1 {
+                    bits: bindings::_VAEncMiscParameterBufferROI__bindgen_ty_1__bindgen_ty_1 {
+                        _bitfield_align_1: Default::default(),
+                        _bitfield_1,
+                    },
+                },
+                ..Default::default()
+            },
+        );
+
+        Self { buffer, rois }
+    }
+
+    /// Checks that this parameter is supported by an encoder with capabilities `caps`.
+    /// `rate_control` is whether bitrate control is enabled, i.e. the rate control mode is not
+    /// `VA_RC_CQP`.
+    pub fn validate(&self, caps: &EncROICaps, rate_control: bool) -> Result<(), EncROIError> {
+        if self.rois.len() > caps.num_roi_regions as usize {
+            return Err(EncROIError::TooManyRegions(
+                self.rois.len(),
+                caps.num_roi_regions,
+            ));
+        }
+
+        let roi_flags = self.buffer.value.roi_flags;
+        // Safe because all bit patterns are valid for this union of integers.
+        let qp_delta = unsafe { roi_flags.bits.roi_value_is_qp_delta() } != 0;
+
+        if rate_control {
+            if qp_delta && !caps.roi_rc_qp_delta_support {
+                return Err(EncROIError::QpDeltaNotSupported);
+            }
+            if !qp_delta && !caps.roi_rc_priority_support {
+                return Err(EncROIError::PriorityNotSupported);
+            }
+        }
+
+        if qp_delta || !rate_control {
+            let (min, max) = (
+                self.buffer.value.min_delta_qp,
+                self.buffer.value.max_delta_qp,
+            );
+            if let Some(roi) = self
+                .rois
+                .iter()
+                .find(|roi| !(min..=max).contains(&roi.roi_value))
+            {
+                return Err(EncROIError::QpDeltaOutOfRange(roi.roi_value, min, max));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterBufferROI> {
+        &self.buffer
+    }
+
+    pub(crate) fn inner_mut(
+        &mut self,
+    ) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterBufferROI> {
+        &mut self.buffer
+    }
+}
+
+/// Rate control extension capabilities of an encoder, as reported by the
+/// `VAConfigAttribEncRateControlExt` attribute.
+///
+/// This is where libva reports the maximum number of temporal layers supported by an encoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncRateControlExtCaps {
+    /// Maximum number of temporal layers supported.
+    pub max_num_temporal_layers: u32,
+    /// Whether each temporal layer can have its own bitrate control parameters.
+    pub temporal_layer_bitrate_control: bool,
+}
+
+impl EncRateControlExtCaps {
+    /// Decodes the value of the `VAConfigAttribEncRateControlExt` attribute.
+    pub fn from_attribute(value: u32) -> Self {
+        let attrib = bindings::VAConfigAttribValEncRateControlExt { value };
+
+        // Safe because all bit patterns are valid for this union of integers.
+        let bits = unsafe { attrib.bits };
+
+        Self {
+            max_num_temporal_layers: bits.max_num_temporal_layers_minus1() + 1,
+            temporal_layer_bitrate_control: bits.temporal_layer_bitrate_control_flag() != 0,
+        }
+    }
+}
+
+/// Error returned when a temporal layer structure is invalid or not supported by the encoder.
+#[derive(Debug, Error)]
+pub enum TemporalLayerError {
+    #[error("the layer ID pattern must contain between 1 and {0} entries, got {1}")]
+    InvalidPeriodicity(usize, usize),
+    #[error("layer ID {0} is out of range for {1} layers")]
+    InvalidLayerId(u32, u32),
+    #[error("{0} temporal layers requested, but the encoder supports at most {1}")]
+    TooManyLayers(u32, u32),
+    #[error("the encoder does not support per-layer bitrate control")]
+    PerLayerRateControlNotSupported,
+}
+
+/// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterTemporalLayerStructure`.
+///
+/// The rate control and frame rate of each layer are then set by sending
+/// [`EncMiscParameterRateControl`] and [`EncMiscParameterFrameRate`] parameters with the
+/// corresponding `temporal_id`.
+pub struct EncMiscParameterTemporalLayerStructure(
+    Box<MiscEncParamBuffer<bindings::VAEncMiscParameterTemporalLayerStructure>>,
+);
+
+impl EncMiscParameterTemporalLayerStructure {
+    /// Creates a structure of `number_of_layers` temporal layers, where the layer of each frame is
+    /// given by `layer_ids`, repeated periodically.
+    pub fn new(number_of_layers: u32, layer_ids: &[u32]) -> Result<Self, TemporalLayerError> {
+        let mut layer_id = [0u32; 32];
+
+        if layer_ids.is_empty() || layer_ids.len() > layer_id.len() {
+            return Err(TemporalLayerError::InvalidPeriodicity(
+                layer_id.len(),
+                layer_ids.len(),
+            ));
+        }
+
+        if let Some(&id) = layer_ids.iter().find(|&&id| id >= number_of_layers) {
+            return Err(TemporalLayerError::InvalidLayerId(id, number_of_layers));
+        }
+
+        layer_id[..layer_ids.len()].copy_from_slice(layer_ids);
+
+        Ok(Self(MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeTemporalLayerStructure,
+            bindings::VAEncMiscParameterTemporalLayerStructure {
+                number_of_layers,
+                periodicity: layer_ids.len() as u32,
+                layer_id,
+                ..Default::default()
+            },
+        )))
+    }
+
+    /// Creates the L1T2 structure, alternating between the base and enhancement layers.
+    pub fn l1t2() -> Self {
+        // Cannot fail as the pattern is valid.
+        Self::new(2, &[0, 1]).unwrap()
+    }
+
+    /// Creates the L1T3 structure, with the `0, 2, 1, 2` layer pattern.
+    pub fn l1t3() -> Self {
+        // Cannot fail as the pattern is valid.
+        Self::new(3, &[0, 2, 1, 2]).unwrap()
+    }
+
+    /// Checks that this structure is supported by an encoder with capabilities `caps`.
+    /// `per_layer_rate_control` is whether rate control parameters are to be sent for each layer.
+    pub fn validate(
+        &self,
+        caps: &EncRateControlExtCaps,
+        per_layer_rate_control: bool,
+    ) -> Result<(), TemporalLayerError> {
+        let number_of_layers = self.0.value.number_of_layers;
+
+        if number_of_layers > caps.max_num_temporal_layers {
+            return Err(TemporalLayerError::TooManyLayers(
+                number_of_layers,
+                caps.max_num_temporal_layers,
+            ));
+        }
+
+        if per_layer_rate_control && !caps.temporal_layer_bitrate_control {
+            return Err(TemporalLayerError::PerLayerRateControlNotSupported);
+        }
+
+        Ok(())
+    }
+
+    pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterTemporalLayerStructure> {
+        &self.0
+    }
+
+    pub(crate) fn inner_mut(
+        &mut self,
+    ) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterTemporalLayerStructure> {
+        &mut self.0
+    }
+}
+
+bitflags! {
+    /// Intra refresh modes supported by an encoder, as reported by the
+    /// `VAConfigAttribEncIntraRefresh` attribute.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct EncIntraRefresh: u32 {
+        /// Rolling intra refresh along columns.
+        const ROLLING_COLUMN = bindings::VA_ENC_INTRA_REFRESH_ROLLING_COLUMN;
+        /// Rolling intra refresh along rows.
+        const ROLLING_ROW = bindings::VA_ENC_INTRA_REFRESH_ROLLING_ROW;
+        /// Adaptive intra refresh.
+        const ADAPTIVE = bindings::VA_ENC_INTRA_REFRESH_ADAPTIVE;
+        /// Cyclic intra refresh.
+        const CYCLIC = bindings::VA_ENC_INTRA_REFRESH_CYCLIC;
+        /// Intra refresh is supported in P frames.
+        const P_FRAME = bindings::VA_ENC_INTRA_REFRESH_P_FRAME;
+        /// Intra refresh is supported in B frames.
+        const B_FRAME = bindings::VA_ENC_INTRA_REFRESH_B_FRAME;
+        /// Intra refresh is supported with multiple reference frames.
+        const MULTI_REF = bindings::VA_ENC_INTRA_REFRESH_MULTI_REF;
+    }
+}
+
+/// Direction of a rolling intra refresh.
+///
+/// Note that libva only provides column and row refresh, there is no other rolling pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollingIntraRefreshMode {
+    /// Refresh columns of blocks, from left to right.
+    Column,
+    /// Refresh rows of blocks, from top to bottom.
+    Row,
+}
+
+impl RollingIntraRefreshMode {
+    /// Returns the flag of `VAConfigAttribEncIntraRefresh` required to use this mode.
+    pub fn required_flag(&self) -> EncIntraRefresh {
+        match self {
+            RollingIntraRefreshMode::Column => EncIntraRefresh::ROLLING_COLUMN,
+            RollingIntraRefreshMode::Row => EncIntraRefresh::ROLLING_ROW,
+        }
+    }
+}
+
+/// Schedule of a rolling intra refresh, spreading the refresh of the whole picture over a number
+/// of frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollingIntraRefreshSchedule {
+    /// Direction of the refresh.
+    pub mode: RollingIntraRefreshMode,
+    /// Number of columns or rows of blocks (e.g. macroblocks) in the picture, depending on `mode`.
+    pub num_units: u16,
+    /// Number of frames over which the whole picture is refreshed.
+    pub period: u16,
+}
+
+impl RollingIntraRefreshSchedule {
+    /// Returns the position and size, in columns or rows of blocks, of the area to refresh in
+    /// frame `frame_index` of the stream.
+    ///
+    /// Every frame refreshes the same number of units, except for the last ones of a period which
+    /// may refresh fewer (possibly zero) if `num_units` is not a multiple of `period`.
+    pub fn position(&self, frame_index: u64) -> (u16, u16) {
+        let period = std::cmp::max(self.period, 1);
+        let size = self.num_units.div_ceil(period);
+        let location = (frame_index % u64::from(period)) as u32 * u32::from(size);
+
+        match u16::try_from(location) {
+            Ok(location) if location < self.num_units => {
+                (location, std::cmp::min(size, self.num_units - location))
+            }
+            _ => (self.num_units, 0),
+        }
+    }
+}
+
+/// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterRIR`.
+pub struct EncMiscParameterRIR(Box<MiscEncParamBuffer<bindings::VAEncMiscParameterRIR>>);
+
+impl EncMiscParameterRIR {
+    /// Creates the wrapper.
+    ///
+    /// `intra_insertion_location` and `intra_insert_size` are the position and size of the
+    /// refreshed area in columns or rows of blocks, depending on `mode`, and
+    /// `qp_delta_for_inserted_intra` the QP delta applied to the refreshed blocks.
+    pub fn new(
+        mode: RollingIntraRefreshMode,
+        intra_insertion_location: u16,
+        intra_insert_size: u16,
+        qp_delta_for_inserted_intra: u8,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAEncMiscParameterRIR__bindgen_ty_1__bindgen_ty_1::new_bitfield_1(
+                (mode == RollingIntraRefreshMode::Column) as u32,
+                (mode == RollingIntraRefreshMode::Row) as u32,
+                Default::default(),
+            );
+
+        Self(MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeRIR,
+            bindings::VAEncMiscParameterRIR {
+                rir_flags: bindings::_VAEncMiscParameterRIR__bindgen_ty_1 {
+                    bits: bindings::_VAEncMiscParameterRIR__bindgen_ty_1__bindgen_ty_1 {
+                        _bitfield_align_1: Default::default(),
+                        _bitfield_1,
+                    },
+                },
+                intra_insertion_location,
+                intra_insert_size,
+                qp_delta_for_inserted_intra,
+                ..Default::default()
+            },
+        ))
+    }
+
+    /// Creates the parameter for frame `frame_index` of the rolling refresh `schedule`.
+    pub fn for_frame(
+        schedule: &RollingIntraRefreshSchedule,
+        frame_index: u64,
+        qp_delta_for_inserted_intra: u8,
+    ) -> Self {
+        let (location, size) = schedule.position(frame_index);
+
+        Self::new(schedule.mode, location, size, qp_delta_for_inserted_intra)
+    }
+
+    pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterRIR> {
+        &self.0
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterRIR> {
+        &mut self.0
+    }
+}
+
+pub struct EncMiscParameterAIR(Box<MiscEncParamBuffer<bindings::VAEncMiscParameterAIR>>);
+
+impl EncMiscParameterAIR {
+    /// Creates the wrapper.
+    ///
+    /// `air_num_mbs` is the number of macroblocks refreshed per frame by adaptive intra refresh,
+    /// and `air_threshold` the threshold above which a macroblock is refreshed. If `air_auto` is
+    /// set, the threshold is tuned by the hardware instead.
+    pub fn new(air_num_mbs: u32, air_threshold: u32, air_auto: bool) -> Self {
+        Self(MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeAIR,
+            bindings::VAEncMiscParameterAIR {
+                air_num_mbs,
+                air_threshold,
+                air_auto: air_auto as u32,
+                ..Default::default()
+            },
+        ))
+    }
+
+    pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterAIR> {
+        &self.0
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterAIR> {
+        &mut self.0
+    }
+}
+
+/// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterBufferMultiPassFrameSize`,
+/// owning the array of QP deltas it points to.
+///
+/// As for [`EncMiscParameterBufferROI`], the [`Buffer`](crate::Buffer) created from this parameter
+/// keeps the array alive until the buffer is destroyed.
+pub struct EncMiscParameterBufferMultiPassFrameSize {
+    buffer: Box<MiscEncParamBuffer<bindings::VAEncMiscParameterBufferMultiPassFrameSize>>,
+    delta_qp: Vec<u8>,
+}
+
+impl EncMiscParameterBufferMultiPassFrameSize {
+    /// Creates the wrapper.
+    ///
+    /// If a frame exceeds `max_frame_size` bytes, the encoder will try again with up to
+    /// `delta_qp.len()` additional passes, each one increasing the QP by the corresponding value
+    /// of `delta_qp`. Only the first 255 values of `delta_qp` are used.
+    pub fn new(max_frame_size: u32, mut delta_qp: Vec<u8>) -> Self {
+        delta_qp.truncate(u8::MAX as usize);
+
+        let buffer = MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeMultiPassFrameSize,
+            bindings::VAEncMiscParameterBufferMultiPassFrameSize {
+                type_: bindings::VAEncMiscParameterType::VAEncMiscParameterTypeMultiPassFrameSize,
+                max_frame_size,
+                num_passes: delta_qp.len() as u8,
+                // The vector's heap storage does not move when `Self` does, and `Buffer` keeps
+                // `Self` alive for as long as the VA buffer exists.
+                delta_qp: delta_qp.as_mut_ptr(),
+                ..Default::default()
+            },
+        );
+
+        Self { buffer, delta_qp }
+    }
+
+    /// Returns the QP deltas of the additional passes.
+    pub fn delta_qp(&self) -> &[u8] {
+        &self.delta_qp
+    }
+
+    pub fn inner(
+        &self,
+    ) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterBufferMultiPassFrameSize> {
+        &self.buffer
+    }
+
+    pub(crate) fn inner_mut(
+        &mut self,
+    ) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterBufferMultiPassFrameSize> {
+        &mut self.buffer
+    }
+}
+
+pub struct EncMiscParameterCustomRoundingControl(
+    Box<MiscEncParamBuffer<bindings::VAEncMiscParameterCustomRoundingControl>>,
+);
+
+impl EncMiscParameterCustomRoundingControl {
+    /// Creates the wrapper.
+    ///
+    /// `rounding_offset_intra` and `rounding_offset_inter` are the custom rounding offsets used
+    /// for the quantization of intra and inter blocks, or `None` to use the driver's default.
+    /// Only their 7 least significant bits are used.
+    pub fn new(rounding_offset_intra: Option<u8>, rounding_offset_inter: Option<u8>) -> Self {
+        let _bitfield_1 = bindings::_VAEncMiscParameterCustomRoundingControl__bindgen_ty_1__bindgen_ty_1::new_bitfield_1(
+            rounding_offset_intra.is_some() as u32,
+            u32::from(rounding_offset_intra.unwrap_or(0) & 0x7f),
+            rounding_offset_inter.is_some() as u32,
+            u32::from(rounding_offset_inter.unwrap_or(0) & 0x7f),
+            Default::default(),
+        );
+
+        Self(MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeCustomRoundingControl,
+            bindings::VAEncMiscParameterCustomRoundingControl {
+                rounding_offset_setting:
+                    bindings::_VAEncMiscParameterCustomRoundingControl__bindgen_ty_1 {
+                        bits:
+                            bindings::_VAEncMiscParameterCustomRoundingControl__bindgen_ty_1__bindgen_ty_1 {
+                                _bitfield_align_1: Default::default(),
+                                _bitfield_1,
+                            },
+                    },
+            },
+        ))
+    }
+
+    pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterCustomRoundingControl> {
+        &self.0
+    }
+
+    pub(crate) fn inner_mut(
+        &mut self,
+    ) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterCustomRoundingControl> {
+        &mut self.0
+    }
+}
+
+bitflags! {
+    /// Inter macroblock partitions that can be disabled with
+    /// [`EncMiscParameterSubMbPartPelH264`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct H264InterMbPartitions: u8 {
+        const P16X16 = 1 << 0;
+        const P16X8 = 1 << 1;
+        const P8X16 = 1 << 2;
+        const P8X8 = 1 << 3;
+        const P8X4 = 1 << 4;
+        const P4X8 = 1 << 5;
+        const P4X4 = 1 << 6;
+    }
+}
+
+pub struct EncMiscParameterSubMbPartPelH264(
+    Box<MiscEncParamBuffer<bindings::VAEncMiscParameterSubMbPartPelH264>>,
+);
+
+impl EncMiscParameterSubMbPartPelH264 {
+    /// Creates the wrapper.
+    ///
+    /// `disabled_partitions` are the inter partitions the encoder must not use, or `None` to let
+    /// the driver decide. `sub_pel_mode` is the motion estimation precision (0 for integer, 1 for
+    /// half-pel and 3 for quarter-pel), or `None` to let the driver decide.
+    pub fn new(
+        disabled_partitions: Option<H264InterMbPartitions>,
+        sub_pel_mode: Option<u8>,
+    ) -> Self {
+        let partitions = disabled_partitions.unwrap_or_default();
+        let has = |partition| partitions.contains(partition) as u32;
+
+        let _bitfield_1 =
+            bindings::_VAEncMiscParameterSubMbPartPelH264__bindgen_ty_1__bindgen_ty_1::new_bitfield_1(
+                has(H264InterMbPartitions::P16X16),
+                has(H264InterMbPartitions::P16X8),
+                has(H264InterMbPartitions::P8X16),
+                has(H264InterMbPartitions::P8X8),
+                has(H264InterMbPartitions::P8X4),
+                has(H264InterMbPartitions::P4X8),
+                has(H264InterMbPartitions::P4X4),
+                Default::default(),
+            );
+
+        Self(MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeSubMbPartPel,
+            bindings::VAEncMiscParameterSubMbPartPelH264 {
+                disable_inter_sub_mb_partition: disabled_partitions.is_some() as u32,
+                inter_sub_mb_partition_mask:
+                    bindings::_VAEncMiscParameterSubMbPartPelH264__bindgen_ty_1 {
+                        bits: bindings::_VAEncMiscParameterSubMbPartPelH264__bindgen_ty_1__bindgen_ty_1 {
+                            _bitfield_align_1: Default::default(),
+                            _bitfield_1,
+                            __bindgen_padding_0: Default::default(),
+                        },
+                    },
+                enable_sub_pel_mode: sub_pel_mode.is_some() as u32,
+                sub_pel_mode: sub_pel_mode.unwrap_or(0),
+                ..Default::default()
+            },
+        ))
+    }
+
+    pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterSubMbPartPelH264> {
+        &self.0
+    }
+
+    pub(crate) fn inner_mut(
+        &mut self,
+    ) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterSubMbPartPelH264> {
+        &mut self.0
+    }
+}
+
+bitflags! {
+    /// Single-bit encoder controls of `VAEncMiscParameterEncQuality`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct EncQualityControls: u32 {
+        const USE_RAW_PIC_FOR_REF = 1 << 0;
+        const SKIP_CHECK_DISABLE = 1 << 1;
+        const FTQ_OVERRIDE = 1 << 2;
+        const FTQ_ENABLE = 1 << 3;
+        const DIRECT_BIAS_ADJUSTMENT_ENABLE = 1 << 7;
+        const GLOBAL_MOTION_BIAS_ADJUSTMENT_ENABLE = 1 << 8;
+        const HME_DISABLE = 1 << 11;
+        const SUPER_HME_DISABLE = 1 << 12;
+        const ULTRA_HME_DISABLE = 1 << 13;
+        const PANIC_MODE_DISABLE = 1 << 14;
+    }
+}
+
+pub struct EncMiscParameterEncQuality(
+    Box<MiscEncParamBuffer<bindings::VAEncMiscParameterEncQuality>>,
+);
+
+impl EncMiscParameterEncQuality {
+    /// Creates the wrapper.
+    ///
+    /// `hme_mv_cost_scaling_factor` and `force_repartition_check` are 2-bit fields and are
+    /// truncated accordingly. The skip threshold lookup tables are indexed by QP and are only
+    /// used if provided.
+    pub fn new(
+        controls: EncQualityControls,
+        hme_mv_cost_scaling_factor: u8,
+        force_repartition_check: u8,
+        ftq_skip_threshold_lut: Option<[u8; 52]>,
+        non_ftq_skip_threshold_lut: Option<[u16; 52]>,
+    ) -> Self {
+        let mut enc_controls = controls.bits()
+            | (u32::from(hme_mv_cost_scaling_factor & 0x3) << 9)
+            | (u32::from(force_repartition_check & 0x3) << 15);
+        // FTQSkipThresholdLUTInput and NonFTQSkipThresholdLUTInput.
+        if ftq_skip_threshold_lut.is_some() {
+            enc_controls |= 1 << 4;
+        }
+        if non_ftq_skip_threshold_lut.is_some() {
+            enc_controls |= 1 << 5;
+        }
+
+        Self(MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeEncQuality,
+            bindings::VAEncMiscParameterEncQuality {
+                __bindgen_anon_1: bindings::_VAEncMiscParameterEncQuality__bindgen_ty_1 {
+                    encControls: enc_controls,
+                },
+                FTQSkipThresholdLUT: ftq_skip_threshold_lut.unwrap_or([0; 52]),
+                NonFTQSkipThresholdLUT: non_ftq_skip_threshold_lut.unwrap_or([0; 52]),
+                ..Default::default()
+            },
+        ))
+    }
+
+    pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterEncQuality> {
+        &self.0
+    }
+
+    pub(crate) fn inner_mut(
+        &mut self,
+    ) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterEncQuality> {
+        &mut self.0
+    }
+}
+
+/// Wrapper over `VAEncMiscParameterBuffer` with `VAEncMiscParameterParallelRateControl`, owning
+/// the array of B frame counts it points to.
+///
+/// As for [`EncMiscParameterBufferROI`], the [`Buffer`](crate::Buffer) created from this parameter
+/// keeps the array alive until the buffer is destroyed.
+pub struct EncMiscParameterParallelRateControl {
+    buffer: Box<MiscEncParamBuffer<bindings::VAEncMiscParameterParallelRateControl>>,
+    num_b_in_gop: Vec<u32>,
+}
+
+impl EncMiscParameterParallelRateControl {
+    /// Creates the wrapper, with `num_b_in_gop` giving the number of B frames per GOP for each
+    /// layer of B frames.
+    pub fn new(mut num_b_in_gop: Vec<u32>) -> Self {
+        let buffer = MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeParallelBRC,
+            bindings::VAEncMiscParameterParallelRateControl {
+                num_layers: num_b_in_gop.len() as u32,
+                // The vector's heap storage does not move when `Self` does, and `Buffer` keeps
+                // `Self` alive for as long as the VA buffer exists.
+                num_b_in_gop: num_b_in_gop.as_mut_ptr(),
+                ..Default::default()
+            },
+        );
+
+        Self {
+            buffer,
+            num_b_in_gop,
+        }
+    }
+
+    /// Returns the number of B frames per GOP for each layer.
+    pub fn num_b_in_gop(&self) -> &[u32] {
+        &self.num_b_in_gop
+    }
+
+    pub fn inner(&self) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterParallelRateControl> {
+        &self.buffer
+    }
+
+    pub(crate) fn inner_mut(
+        &mut self,
+    ) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterParallelRateControl> {
+        &mut self.buffer
+    }
+}
+
+pub struct EncMiscParameterExtensionDataSeqDisplayMPEG2(
+    Box<MiscEncParamBuffer<bindings::VAEncMiscParameterExtensionDataSeqDisplayMPEG2>>,
+);
+
+impl EncMiscParameterExtensionDataSeqDisplayMPEG2 {
+    /// Identifier of the sequence display extension, as per table 6-2 of the MPEG-2 spec.
+    const SEQUENCE_DISPLAY_EXTENSION_ID: u8 = 0x2;
+
+    /// Creates the wrapper.
+    ///
+    /// `colour_description` holds `colour_primaries`, `transfer_characteristics` and
+    /// `matrix_coefficients`, in this order, or is `None` if the extension has no colour
+    /// description.
+    pub fn new(
+        video_format: u8,
+        colour_description: Option<(u8, u8, u8)>,
+        display_horizontal_size: u16,
+        display_vertical_size: u16,
+    ) -> Self {
+        let (colour_primaries, transfer_characteristics, matrix_coefficients) =
+            colour_description.unwrap_or_default();
+
+        Self(MiscEncParamBuffer::new_boxed(
+            bindings::VAEncMiscParameterType::VAEncMiscParameterTypeExtensionData,
+            bindings::VAEncMiscParameterExtensionDataSeqDisplayMPEG2 {
+                extension_start_code_identifier: Self::SEQUENCE_DISPLAY_EXTENSION_ID,
+                video_format,
+                colour_description: colour_description.is_some() as u8,
+                colour_primaries,
+                transfer_characteristics,
+                matrix_coefficients,
+                display_horizontal_size,
+                display_vertical_size,
+            },
+        ))
+    }
+
+    pub fn inner(
+        &self,
+    ) -> &MiscEncParamBuffer<bindings::VAEncMiscParameterExtensionDataSeqDisplayMPEG2> {
+        &self.0
+    }
+
+    pub(crate) fn inner_mut(
+        &mut self,
+    ) -> &mut MiscEncParamBuffer<bindings::VAEncMiscParameterExtensionDataSeqDisplayMPEG2> {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_intra_refresh_positions() {
+        let schedule = RollingIntraRefreshSchedule {
+            mode: RollingIntraRefreshMode::Row,
+            num_units: 10,
+            period: 4,
+        };
+
+        let positions: Vec<_> = (0..5).map(|i| schedule.position(i)).collect();
+        assert_eq!(positions, [(0, 3), (3, 3), (6, 3), (9, 1), (0, 3)]);
+
+        let schedule = RollingIntraRefreshSchedule {
+            period: 6,
+            ..schedule
+        };
+        assert_eq!(schedule.position(4), (8, 2));
+        assert_eq!(schedule.position(5), (10, 0));
+    }
+
+    #[test]
+    fn roi_payload_offset() {
+        type Buffer = MiscEncParamBuffer<bindings::VAEncMiscParameterBufferROI>;
+
+        assert_eq!(std::mem::offset_of!(Buffer, value), 4);
+        assert_eq!(
+            std::mem::size_of::<Buffer>(),
+            4 + std::mem::size_of::<bindings::VAEncMiscParameterBufferROI>()
+        );
+    }
+
+    #[test]
+    fn multi_pass_frame_size_payload_offset() {
+        type Buffer = MiscEncParamBuffer<bindings::VAEncMiscParameterBufferMultiPassFrameSize>;
+
+        assert_eq!(std::mem::offset_of!(Buffer, value), 4);
+        assert_eq!(
+            std::mem::size_of::<Buffer>(),
+            4 + std::mem::size_of::<bindings::VAEncMiscParameterBufferMultiPassFrameSize>()
+        );
+    }
+
+    #[test]
+    fn parallel_rate_control_payload_offset() {
+        type Buffer = MiscEncParamBuffer<bindings::VAEncMiscParameterParallelRateControl>;
+
+        assert_eq!(std::mem::offset_of!(Buffer, value), 4);
+        assert_eq!(
+            std::mem::size_of::<Buffer>(),
+            4 + std::mem::size_of::<bindings::VAEncMiscParameterParallelRateControl>()
+        );
+    }
+
+    #[test]
+    fn roi_validation() {
+        let caps = EncROICaps {
+            num_roi_regions: 2,
+            roi_rc_priority_support: true,
+            roi_rc_qp_delta_support: false,
+        };
+        let region = |roi_value| {
+            EncROI::new(
+                bindings::VARectangle {
+                    x: 0,
+                    y: 0,
+                    width: 16,
+                    height: 16,
+                },
+                roi_value,
+            )
+        };
+
+        let roi =
+            EncMiscParameterBufferROI::new(vec![region(-4), region(3)], EncROIMode::QpDelta, -4, 3);
+        assert!(roi.validate(&caps, false).is_ok());
+        assert!(matches!(
+            roi.validate(&caps, true),
+            Err(EncROIError::QpDeltaNotSupported)
+        ));
+
+        let roi = EncMiscParameterBufferROI::new(
+            vec![region(0), region(0), region(0)],
+            EncROIMode::QpDelta,
+            -4,
+            3,
+        );
+        assert!(matches!(
+            roi.validate(&caps, false),
+            Err(EncROIError::TooManyRegions(3, 2))
+        ));
+
+        let roi = EncMiscParameterBufferROI::new(vec![region(-5)], EncROIMode::QpDelta, -4, 3);
+        assert!(matches!(
+            roi.validate(&caps, false),
+            Err(EncROIError::QpDeltaOutOfRange(-5, -4, 3))
+        ));
+
+        // Priorities are not QP deltas and are not bound by the QP range under bitrate control.
+        let roi = EncMiscParameterBufferROI::new(vec![region(10)], EncROIMode::Priority, -4, 3);
+        assert!(roi.validate(&caps, true).is_ok());
+        let caps = EncROICaps {
+            roi_rc_priority_support: false,
+            ..caps
+        };
+        assert!(matches!(
+            roi.validate(&caps, true),
+            Err(EncROIError::PriorityNotSupported)
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Wrappers around packed header `VABuffer` types.
+
+use bitflags::bitflags;
+
+use crate::bindings;
+use crate::buffer::BufferType;
+
+bitflags! {
+    /// Packed headers supported by the driver, as reported by the `VAConfigAttribEncPackedHeaders`
+    /// attribute.
+    ///
+    /// The headers to be provided by the application must also be set using this attribute when
+    /// creating the encoding config.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct EncPackedHeaders: u32 {
+        /// Sequence headers (SPS, VPS, sequence header OBU...).
+        const SEQUENCE = bindings::VA_ENC_PACKED_HEADER_SEQUENCE;
+        /// Picture headers (PPS, frame header OBU...).
+        const PICTURE = bindings::VA_ENC_PACKED_HEADER_PICTURE;
+        /// Slice headers.
+        const SLICE = bindings::VA_ENC_PACKED_HEADER_SLICE;
+        /// Miscellaneous headers.
+        const MISC = bindings::VA_ENC_PACKED_HEADER_MISC;
+        /// Raw data, e.g. SEI messages, inserted in the stream as-is.
+        const RAW_DATA = bindings::VA_ENC_PACKED_HEADER_RAW_DATA;
+    }
+}
+
+impl EncPackedHeaders {
+    /// Returns whether headers of type `type_` can be provided by the application.
+    pub fn supports(&self, type_: EncPackedHeaderType) -> bool {
+        self.contains(type_.required_flag())
+    }
+}
+
+/// Kind of a packed header, i.e. `VAEncPackedHeaderType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncPackedHeaderType {
+    /// `VAEncPackedHeaderSequence`.
+    Sequence,
+    /// `VAEncPackedHeaderPicture`.
+    Picture,
+    /// `VAEncPackedHeaderSlice`.
+    Slice,
+    /// `VAEncPackedHeaderRawData`.
+    RawData,
+    /// Codec-specific miscellaneous header, i.e. `VAEncPackedHeaderMiscMask` combined with the
+    /// given value.
+    Misc(u32),
+}
+
+impl EncPackedHeaderType {
+    /// Returns the `VAEncPackedHeaderType` value of this type.
+    pub fn inner(&self) -> bindings::VAEncPackedHeaderType::Type {
+        match self {
+            EncPackedHeaderType::Sequence => {
+                bindings::VAEncPackedHeaderType::VAEncPackedHeaderSequence
+            }
+            EncPackedHeaderType::Picture => {
+                bindings::VAEncPackedHeaderType::VAEncPackedHeaderPicture
+            }
+            EncPackedHeaderType::Slice => bindings::VAEncPackedHeaderType::VAEncPackedHeaderSlice,
+            EncPackedHeaderType::RawData => {
+                bindings::VAEncPackedHeaderType::VAEncPackedHeaderRawData
+            }
+            EncPackedHeaderType::Misc(value) => {
+                bindings::VAEncPackedHeaderType::VAEncPackedHeaderMiscMask | value
+            }
+        }
+    }
+
+    /// Returns the flag of `VAConfigAttribEncPackedHeaders` required to submit headers of this
+    /// type.
+    pub fn required_flag(&self) -> EncPackedHeaders {
+        match self {
+            EncPackedHeaderType::Sequence => EncPackedHeaders::SEQUENCE,
+            EncPackedHeaderType::Picture => EncPackedHeaders::PICTURE,
+            EncPackedHeaderType::Slice => EncPackedHeaders::SLICE,
+            EncPackedHeaderType::RawData => EncPackedHeaders::RAW_DATA,
+            EncPackedHeaderType::Misc(_) => EncPackedHeaders::MISC,
+        }
+    }
+}
+
+/// Wrapper over the `VAEncPackedHeaderParameterBuffer` FFI type.
+pub struct EncPackedHeaderParameterBuffer(Box<bindings::VAEncPackedHeaderParameterBuffer>);
+
+impl EncPackedHeaderParameterBuffer {
+    /// Creates the wrapper.
+    ///
+    /// `bit_length` is the length of the header data in bits, and `has_emulation_bytes` whether
+    /// the data already contains emulation prevention bytes.
+    pub fn new(type_: EncPackedHeaderType, bit_length: u32, has_emulation_bytes: bool) -> Self {
+        Self(Box::new(bindings::VAEncPackedHeaderParameterBuffer {
+            type_: type_.inner(),
+            bit_length,
+            has_emulation_bytes: has_emulation_bytes as u8,
+            ..Default::default()
+        }))
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAEncPackedHeaderParameterBuffer {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAEncPackedHeaderParameterBuffer {
+        self.0.as_ref()
+    }
+}
+
+/// A header generated by the application, to be inserted into the encoded stream.
+///
+/// It is submitted as a pair of buffers: a `VAEncPackedHeaderParameterBufferType` buffer
+/// describing the header, immediately followed by a `VAEncPackedHeaderDataBufferType` buffer
+/// containing it.
+pub struct EncPackedHeader {
+    param: EncPackedHeaderParameterBuffer,
+    data: Vec<u8>,
+}
+
+impl EncPackedHeader {
+    /// Creates a packed header of type `type_` from the first `bit_length` bits of `data`.
+    ///
+    /// Returns `None` if `data` is shorter than `bit_length` bits.
+    pub fn new(
+        type_: EncPackedHeaderType,
+        data: Vec<u8>,
+        bit_length: u32,
+        has_emulation_bytes: bool,
+    ) -> Option<Self> {
+        if (bit_length as usize).div_ceil(8) > data.len() {
+            return None;
+        }
+
+        Some(Self {
+            param: EncPackedHeaderParameterBuffer::new(type_, bit_length, has_emulation_bytes),
+            data,
+        })
+    }
+
+    /// Creates a packed header of type `type_` using all the bytes of `data`.
+    pub fn from_bytes(
+        type_: EncPackedHeaderType,
+        data: Vec<u8>,
+        has_emulation_bytes: bool,
+    ) -> Self {
+        Self {
+            param: EncPackedHeaderParameterBuffer::new(
+                type_,
+                (data.len() * 8) as u32,
+                has_emulation_bytes,
+            ),
+            data,
+        }
+    }
+
+    /// Returns the parameter and data buffer types, in the order they must be submitted.
+    pub fn into_buffer_types(self) -> (BufferType, BufferType) {
+        (
+            BufferType::EncPackedHeaderParameter(self.param),
+            BufferType::EncPackedHeaderData(self.data),
+        )
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Wrapper around the per-block QP map used as encoder input.
+
+use std::rc::Rc;
+
+use crate::bindings;
+use crate::buffer::Buffer;
+use crate::buffer::Buffer2D;
+use crate::buffer::MapBufferError;
+use crate::buffer::MappedBuffer2D;
+use crate::Context;
+use crate::VaError;
+
+/// Wrapper over a `VAEncQPBufferType` buffer, holding one QP value per block of the coded
+/// picture.
+///
+/// The size of a block is given by the `VAConfigAttribQPBlockSize` attribute, see
+/// [`crate::Display::query_qp_block_size`]. The map is allocated with `vaCreateBuffer2` so the
+/// layout of its rows follows the pitch chosen by the driver.
+pub struct EncQPMap {
+    buffer: Buffer2D,
+    block_size: u32,
+}
+
+impl EncQPMap {
+    /// Creates a new QP map for a coded picture of `coded_width`x`coded_height` pixels split into
+    /// square blocks of `block_size` pixels. This is just a helper for
+    /// [`Context::create_enc_qp_map`].
+    pub(crate) fn new(
+        context: Rc<Context>,
+        block_size: u32,
+        coded_width: u32,
+        coded_height: u32,
+    ) -> Result<Self, VaError> {
+        let (width, height) = Self::dimensions(block_size, coded_width, coded_height);
+        let buffer = Buffer2D::new(
+            context,
+            bindings::VABufferType::VAEncQPBufferType,
+            width,
+            height,
+        )?;
+
+        Ok(Self { buffer, block_size })
+    }
+
+    /// Returns the number of blocks per row and column of a QP map for a coded picture of
+    /// `coded_width`x`coded_height` pixels and blocks of `block_size` pixels.
+    pub fn dimensions(block_size: u32, coded_width: u32, coded_height: u32) -> (u32, u32) {
+        let block_size = std::cmp::max(block_size, 1);
+
+        (
+            coded_width.div_ceil(block_size),
+            coded_height.div_ceil(block_size),
+        )
+    }
+
+    /// Returns the size of a block, in pixels.
+    pub fn block_size(&self) -> u32 {
+        self.block_size
+    }
+
+    /// Returns the number of blocks in a row of the map.
+    pub fn width(&self) -> u32 {
+        self.buffer.width()
+    }
+
+    /// Returns the number of rows of blocks in the map.
+    pub fn height(&self) -> u32 {
+        self.buffer.height()
+    }
+
+    /// Returns the size of the unit holding the QP of a block, in bytes, as returned by the driver.
+    pub fn unit_size(&self) -> u32 {
+        self.buffer.unit_size()
+    }
+
+    /// Returns the distance between two rows of the map, in bytes, as returned by the driver.
+    pub fn pitch(&self) -> u32 {
+        self.buffer.pitch()
+    }
+
+    /// Returns the underlying buffer.
+    pub fn buffer(&self) -> &Buffer {
+        self.buffer.buffer()
+    }
+
+    /// Consumes the map and returns the underlying buffer, e.g. to add it to a
+    /// [`crate::Picture`].
+    pub fn into_buffer(self) -> Buffer {
+        self.buffer.into_buffer()
+    }
+
+    /// Maps the QP map for writing.
+    pub fn map(&mut self) -> Result<EncQPMapWriter, MapBufferError> {
+        let width = self.buffer.width() as usize;
+        let unit_size = self.buffer.unit_size() as usize;
+        if unit_size == 0 {
+            return Err(MapBufferError::InvalidPitch);
+        }
+
+        Ok(EncQPMapWriter {
+            mapping: self.buffer.map()?,
+            width,
+            unit_size,
+        })
+    }
+}
+
+impl AsRef<Buffer> for EncQPMap {
+    fn as_ref(&self) -> &Buffer {
+        self.buffer.as_ref()
+    }
+}
+
+/// Writer over a mapped [`EncQPMap`].
+///
+/// Each QP value is written into the first byte of its block's unit, the remaining bytes of the
+/// unit (if the driver uses units larger than one byte) being left to zero. The buffer is unmapped
+/// when this object is dropped.
+pub struct EncQPMapWriter<'a> {
+    mapping: MappedBuffer2D<'a>,
+    width: usize,
+    unit_size: usize,
+}
+
+impl<'a> EncQPMapWriter<'a> {
+    /// Returns the number of blocks in a row of the map.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows of blocks in the map.
+    pub fn height(&self) -> usize {
+        self.mapping.height()
+    }
+
+    /// Sets the QP of block (`x`, `y`). Returns `false` if the block is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, qp: u8) -> bool {
+        if x >= self.width {
+            return false;
+        }
+
+        let unit_size = self.unit_size;
+        match self.mapping.row_mut(y) {
+            Some(row) => {
+                let unit = &mut row[x * unit_size..][..unit_size];
+                unit.fill(0);
+                unit[0] = qp;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the QP of all the blocks of the map to `qp`.
+    pub fn fill(&mut self, qp: u8) {
+        let unit_size = self.unit_size;
+
+        for row in self.mapping.rows_mut() {
+            for unit in row.chunks_mut(unit_size) {
+                unit.fill(0);
+                unit[0] = qp;
+            }
+        }
+    }
+
+    /// Writes the whole map from `qps`, which contains the QP of each block in raster order, i.e.
+    /// `width` values for each of the `height` rows. Returns `false` and leaves the map untouched
+    /// if `qps` does not have exactly one value per block.
+    pub fn write(&mut self, qps: &[u8]) -> bool {
+        if qps.len() != self.width * self.height() {
+            return false;
+        }
+
+        let (width, unit_size) = (self.width, self.unit_size);
+        for (row, qps) in self.mapping.rows_mut().zip(qps.chunks(width)) {
+            for (unit, &qp) in row.chunks_mut(unit_size).zip(qps) {
+                unit.fill(0);
+                unit[0] = qp;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qp_map_dimensions() {
+        assert_eq!(EncQPMap::dimensions(16, 64, 64), (4, 4));
+        // Partial blocks at the right and bottom edges get their own QP.
+        assert_eq!(EncQPMap::dimensions(16, 65, 33), (5, 3));
+        assert_eq!(EncQPMap::dimensions(64, 1, 1), (1, 1));
+        assert_eq!(EncQPMap::dimensions(16, 0, 0), (0, 0));
+        // A block size of 0 is handled as one block per pixel.
+        assert_eq!(EncQPMap::dimensions(0, 10, 7), (10, 7));
+    }
+}
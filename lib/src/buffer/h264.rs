@@ -183,6 +183,26 @@ impl PictureParameterBufferH264 {
         }))
     }
 
+    /// Sets the current picture.
+    pub fn set_curr_pic(&mut self, curr_pic: PictureH264) {
+        self.0.CurrPic = curr_pic.0;
+    }
+
+    /// Sets the reference frames.
+    pub fn set_reference_frames(&mut self, reference_frames: [PictureH264; 16]) {
+        self.0.ReferenceFrames = reference_frames.map(|pic| pic.0);
+    }
+
+    /// Sets the `frame_num` of the current picture.
+    pub fn set_frame_num(&mut self, frame_num: u16) {
+        self.0.frame_num = frame_num;
+    }
+
+    /// Sets the picture fields.
+    pub fn set_pic_fields(&mut self, pic_fields: &H264PicFields) {
+        self.0.pic_fields = pic_fields.0;
+    }
+
     pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAPictureParameterBufferH264 {
         self.0.as_mut()
     }
@@ -348,6 +368,12 @@ impl SliceParameterBufferH264 {
         self.0.push(buf);
     }
 
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
     pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferH264> {
         &mut self.0
     }
@@ -657,6 +683,46 @@ impl EncPictureParameterBufferH264 {
         }))
     }
 
+    /// Sets the current picture.
+    pub fn set_curr_pic(&mut self, curr_pic: PictureH264) {
+        self.0.CurrPic = curr_pic.0;
+    }
+
+    /// Sets the reference frames.
+    pub fn set_reference_frames(&mut self, reference_frames: [PictureH264; 16]) {
+        self.0.ReferenceFrames = reference_frames.map(|pic| pic.0);
+    }
+
+    /// Sets the coded buffer the picture is to be encoded into.
+    pub fn set_coded_buf(&mut self, coded_buf: bindings::VABufferID) {
+        self.0.coded_buf = coded_buf;
+    }
+
+    /// Sets whether this is the last picture of the stream.
+    pub fn set_last_picture(&mut self, last_picture: u8) {
+        self.0.last_picture = last_picture;
+    }
+
+    /// Sets the `frame_num` of the current picture.
+    pub fn set_frame_num(&mut self, frame_num: u16) {
+        self.0.frame_num = frame_num;
+    }
+
+    /// Sets the initial QP of the picture.
+    pub fn set_pic_init_qp(&mut self, pic_init_qp: u8) {
+        self.0.pic_init_qp = pic_init_qp;
+    }
+
+    /// Sets the picture fields.
+    pub fn set_pic_fields(&mut self, pic_fields: &H264EncPicFields) {
+        self.0.pic_fields = pic_fields.0;
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAEncPictureParameterBufferH264 {
+        self.0.as_ref()
+    }
+
     pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAEncPictureParameterBufferH264 {
         self.0.as_mut()
     }
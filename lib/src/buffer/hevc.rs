@@ -487,7 +487,7 @@ impl HevcLongSliceFlags {
 }
 
 /// A wrapper over `VASliceParameterBufferHEVC` FFI type
-pub struct SliceParameterBufferHEVC(Box<bindings::VASliceParameterBufferHEVC>);
+pub struct SliceParameterBufferHEVC(Vec<bindings::VASliceParameterBufferHEVC>);
 
 impl SliceParameterBufferHEVC {
     /// Creates the wrapper
@@ -525,7 +525,7 @@ impl SliceParameterBufferHEVC {
     ) -> Self {
         let long_slice_flags = long_slice_flags.0;
 
-        Self(Box::new(bindings::VASliceParameterBufferHEVC {
+        Self(vec![bindings::VASliceParameterBufferHEVC {
             slice_data_size,
             slice_data_offset,
             slice_data_flag,
@@ -556,26 +556,34 @@ impl SliceParameterBufferHEVC {
             entry_offset_to_subset_array,
             slice_data_num_emu_prevn_bytes,
             va_reserved: Default::default(),
-        }))
+        }])
     }
 
-    /// Set this slice as the last one after creation. Implementations may only
-    /// be able to conveniently see if this is the last slice after it has been
-    /// created.
+    /// Set the last slice of this buffer as the last one of the picture after
+    /// creation. Implementations may only be able to conveniently see if this
+    /// is the last slice after it has been created.
     pub fn set_as_last(&mut self) {
-        // Safe because we know that both fields are valid at all times (just a
-        // different view on the data), and we are mutating through the bindgen
-        // function, respecting the padding in place.
-        unsafe { self.inner_mut().LongSliceFlags.fields.set_LastSliceOfPic(1) };
+        if let Some(slice) = self.0.last_mut() {
+            // Safe because we know that both fields are valid at all times (just a
+            // different view on the data), and we are mutating through the bindgen
+            // function, respecting the padding in place.
+            unsafe { slice.LongSliceFlags.fields.set_LastSliceOfPic(1) };
+        }
     }
 
-    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VASliceParameterBufferHEVC {
-        self.0.as_mut()
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferHEVC> {
+        &mut self.0
     }
 
     /// Returns the inner FFI type. Useful for testing purposes.
-    pub fn inner(&self) -> &bindings::VASliceParameterBufferHEVC {
-        self.0.as_ref()
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferHEVC> {
+        &self.0
     }
 }
 
@@ -608,7 +616,7 @@ impl HevcSliceExtFlags {
 }
 
 /// A wrapper over `VASliceParameterBufferHEVCRext` FFI type
-pub struct SliceParameterBufferHEVCRext(Box<bindings::VASliceParameterBufferHEVCRext>);
+pub struct SliceParameterBufferHEVCRext(Vec<bindings::VASliceParameterBufferHEVCRext>);
 
 impl SliceParameterBufferHEVCRext {
     /// Creates the wrapper
@@ -625,7 +633,7 @@ impl SliceParameterBufferHEVCRext {
     ) -> Self {
         let slice_ext_flags = slice_ext_flags.0;
 
-        Self(Box::new(bindings::VASliceParameterBufferHEVCRext {
+        Self(vec![bindings::VASliceParameterBufferHEVCRext {
             luma_offset_l0,
             ChromaOffsetL0: chroma_offset_l0,
             luma_offset_l1,
@@ -634,16 +642,22 @@ impl SliceParameterBufferHEVCRext {
             slice_act_y_qp_offset,
             slice_act_cb_qp_offset,
             slice_act_cr_qp_offset,
-        }))
+        }])
     }
 
-    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VASliceParameterBufferHEVCRext {
-        self.0.as_mut()
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferHEVCRext> {
+        &mut self.0
     }
 
     /// Returns the inner FFI type. Useful for testing purposes.
-    pub fn inner(&self) -> &bindings::VASliceParameterBufferHEVCRext {
-        self.0.as_ref()
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferHEVCRext> {
+        &self.0
     }
 }
 
@@ -1016,6 +1030,51 @@ impl EncPictureParameterBufferHEVC {
         }))
     }
 
+    /// Sets the current picture.
+    pub fn set_decoded_curr_pic(&mut self, decoded_curr_pic: PictureHEVC) {
+        self.0.decoded_curr_pic = decoded_curr_pic.0;
+    }
+
+    /// Sets the reference frames.
+    pub fn set_reference_frames(&mut self, reference_frames: [PictureHEVC; 15]) {
+        self.0.reference_frames = reference_frames.map(|pic| pic.0);
+    }
+
+    /// Sets the coded buffer the picture is to be encoded into.
+    pub fn set_coded_buf(&mut self, coded_buf: bindings::VABufferID) {
+        self.0.coded_buf = coded_buf;
+    }
+
+    /// Sets the index of the collocated reference picture.
+    pub fn set_collocated_ref_pic_index(&mut self, collocated_ref_pic_index: u8) {
+        self.0.collocated_ref_pic_index = collocated_ref_pic_index;
+    }
+
+    /// Sets whether this is the last picture of the stream.
+    pub fn set_last_picture(&mut self, last_picture: u8) {
+        self.0.last_picture = last_picture;
+    }
+
+    /// Sets the initial QP of the picture.
+    pub fn set_pic_init_qp(&mut self, pic_init_qp: u8) {
+        self.0.pic_init_qp = pic_init_qp;
+    }
+
+    /// Sets the NAL unit type of the picture.
+    pub fn set_nal_unit_type(&mut self, nal_unit_type: u8) {
+        self.0.nal_unit_type = nal_unit_type;
+    }
+
+    /// Sets the picture fields.
+    pub fn set_pic_fields(&mut self, pic_fields: &HEVCEncPicFields) {
+        self.0.pic_fields = pic_fields.0;
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAEncPictureParameterBufferHEVC {
+        &self.0
+    }
+
     pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAEncPictureParameterBufferHEVC {
         &mut self.0
     }
@@ -97,7 +97,7 @@ impl VASliceParameterBufferJPEGBaselineComponent {
 }
 
 /// Wrapper over the `VASliceParameterBufferJPEGBaseline` FFI type.
-pub struct SliceParameterBufferJPEGBaseline(Box<bindings::VASliceParameterBufferJPEGBaseline>);
+pub struct SliceParameterBufferJPEGBaseline(Vec<bindings::VASliceParameterBufferJPEGBaseline>);
 
 impl SliceParameterBufferJPEGBaseline {
     /// Creates the wrapper.
@@ -112,7 +112,7 @@ impl SliceParameterBufferJPEGBaseline {
         restart_interval: u16,
         num_mcus: u32,
     ) -> Self {
-        Self(Box::new(bindings::VASliceParameterBufferJPEGBaseline {
+        Self(vec![bindings::VASliceParameterBufferJPEGBaseline {
             slice_data_size,
             slice_data_offset,
             slice_data_flag,
@@ -123,16 +123,22 @@ impl SliceParameterBufferJPEGBaseline {
             restart_interval,
             num_mcus,
             va_reserved: Default::default(),
-        }))
+        }])
     }
 
-    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VASliceParameterBufferJPEGBaseline {
-        self.0.as_mut()
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferJPEGBaseline> {
+        &mut self.0
     }
 
     /// Returns the inner FFI type. Useful for testing purposes.
-    pub fn inner(&mut self) -> &bindings::VASliceParameterBufferJPEGBaseline {
-        self.0.as_ref()
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferJPEGBaseline> {
+        &self.0
     }
 }
 
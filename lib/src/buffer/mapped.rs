@@ -0,0 +1,247 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Mapping of the content of generic `VABuffer`s.
+
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+use log::error;
+use thiserror::Error;
+
+use crate::bindings;
+use crate::buffer::Buffer;
+use crate::va_check;
+use crate::VaError;
+
+/// Marker trait for types that can be read from and written into a mapped [`Buffer`].
+///
+/// # Safety
+///
+/// Implementors must be plain old data: every bit pattern must be a valid value of the type, and
+/// the type must not contain any pointer or reference.
+pub unsafe trait BufferElement: Copy {}
+
+// Safe because all bit patterns are valid values of these primitive types.
+unsafe impl BufferElement for u8 {}
+unsafe impl BufferElement for u16 {}
+unsafe impl BufferElement for u32 {}
+unsafe impl BufferElement for u64 {}
+unsafe impl BufferElement for i8 {}
+unsafe impl BufferElement for i16 {}
+unsafe impl BufferElement for i32 {}
+unsafe impl BufferElement for i64 {}
+
+/// Error type for [`Buffer::map`] and [`Buffer::map_mut`].
+#[derive(Debug, Error)]
+pub enum MapBufferError {
+    #[error("call to vaMapBuffer failed: {0}")]
+    VaError(#[from] VaError),
+    #[error("the buffer is already mapped")]
+    AlreadyMapped,
+    #[error("the mapped buffer is not suitably aligned for the requested type")]
+    Misaligned,
+    #[error("the pitch of the buffer is smaller than its rows")]
+    InvalidPitch,
+}
+
+/// Information about a buffer, as returned by `vaBufferInfo`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferInfo {
+    /// Type of the buffer.
+    pub type_: bindings::VABufferType::Type,
+    /// Size of a single element of the buffer, in bytes.
+    pub size: u32,
+    /// Number of elements in the buffer.
+    pub num_elements: u32,
+}
+
+impl Buffer {
+    /// Returns the type, element size and number of elements of this buffer by wrapping around
+    /// `vaBufferInfo`.
+    pub fn info(&self) -> Result<BufferInfo, VaError> {
+        let mut type_ = 0;
+        let mut size = 0;
+        let mut num_elements = 0;
+
+        // Safe because `self` represents a valid buffer.
+        va_check(unsafe {
+            bindings::vaBufferInfo(
+                self.context.display().handle(),
+                self.id,
+                &mut type_,
+                &mut size,
+                &mut num_elements,
+            )
+        })?;
+
+        Ok(BufferInfo {
+            type_,
+            size,
+            num_elements,
+        })
+    }
+
+    /// Sets the number of elements of this buffer by wrapping around `vaBufferSetNumElements`.
+    ///
+    /// The new number cannot exceed the number of elements the buffer has been created with, and
+    /// can only be changed before the buffer is mapped or submitted.
+    pub fn set_num_elements(&mut self, num_elements: u32) -> Result<(), VaError> {
+        // Safe because `self` represents a valid buffer.
+        va_check(unsafe {
+            bindings::vaBufferSetNumElements(self.context.display().handle(), self.id, num_elements)
+        })
+    }
+
+    /// Maps the buffer with `vaMapBuffer` and returns its content as a slice of `T`.
+    ///
+    /// Trailing bytes that do not make a whole `T` are not part of the slice. The buffer is
+    /// unmapped when the returned [`MappedBuffer`] is dropped, and cannot be mapped again until
+    /// then.
+    pub fn map<T: BufferElement>(&self) -> Result<MappedBuffer<T>, MapBufferError> {
+        let (addr, len) = self.map_raw::<T>(None)?;
+
+        // Safe because `map_raw` checked that `addr` is aligned for `T` and mapped for at least
+        // `len` elements of `T`, which are valid for any bit pattern. The mapping stays alive
+        // until `MappedBuffer` is dropped.
+        let data = unsafe { std::slice::from_raw_parts(addr, len) };
+
+        Ok(MappedBuffer { buffer: self, data })
+    }
+
+    /// Maps the buffer with `vaMapBuffer` and returns its content as a mutable slice of `T`, e.g.
+    /// to fill the buffer in place.
+    ///
+    /// Trailing bytes that do not make a whole `T` are not part of the slice. The buffer is
+    /// unmapped when the returned [`MappedBufferMut`] is dropped, and cannot be mapped again until
+    /// then.
+    pub fn map_mut<T: BufferElement>(&self) -> Result<MappedBufferMut<T>, MapBufferError> {
+        let (addr, len) = self.map_raw::<T>(None)?;
+
+        // Safe because `map_raw` checked that `addr` is aligned for `T` and mapped for at least
+        // `len` elements of `T`, which are valid for any bit pattern. `map_raw` also refuses to map
+        // the buffer again until `MappedBufferMut` is dropped, so this is the only reference to
+        // the mapped data.
+        let data = unsafe { std::slice::from_raw_parts_mut(addr, len) };
+
+        Ok(MappedBufferMut { buffer: self, data })
+    }
+
+    /// Maps the buffer and returns its content as a mutable byte slice of `len` bytes. This is
+    /// for buffers whose size is not reported accurately by `vaBufferInfo`, like 2D buffers.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must be at least `len` bytes large.
+    pub(crate) unsafe fn map_mut_with_len(
+        &self,
+        len: usize,
+    ) -> Result<MappedBufferMut<u8>, MapBufferError> {
+        let (addr, len) = self.map_raw::<u8>(Some(len))?;
+
+        // Safe because the caller guarantees that the buffer is at least `len` bytes large.
+        // `map_raw` refuses to map the buffer again until `MappedBufferMut` is dropped, so this is
+        // the only reference to the mapped data.
+        let data = unsafe { std::slice::from_raw_parts_mut(addr, len) };
+
+        Ok(MappedBufferMut { buffer: self, data })
+    }
+
+    /// Maps the buffer and returns its address and the number of `T` it can hold.
+    ///
+    /// The size of the buffer is `len_bytes` if specified, or obtained from `vaBufferInfo`
+    /// otherwise.
+    fn map_raw<T: BufferElement>(
+        &self,
+        len_bytes: Option<usize>,
+    ) -> Result<(*mut T, usize), MapBufferError> {
+        if self.mapped.get() {
+            return Err(MapBufferError::AlreadyMapped);
+        }
+
+        let len_bytes = match len_bytes {
+            Some(len_bytes) => len_bytes,
+            None => {
+                let info = self.info()?;
+                info.size as usize * info.num_elements as usize
+            }
+        };
+        let mut addr = std::ptr::null_mut();
+
+        // Safe because `self` represents a valid buffer.
+        va_check(unsafe {
+            bindings::vaMapBuffer(self.context.display().handle(), self.id, &mut addr)
+        })?;
+        self.mapped.set(true);
+
+        let addr = addr as *mut T;
+        if addr.is_null() || addr.align_offset(std::mem::align_of::<T>()) != 0 {
+            self.unmap();
+            return Err(MapBufferError::Misaligned);
+        }
+
+        let len = len_bytes / std::mem::size_of::<T>();
+
+        Ok((addr, len))
+    }
+
+    /// Unmaps the buffer previously mapped with `map_raw`.
+    fn unmap(&self) {
+        // Safe because `self` represents a valid buffer that is currently mapped.
+        let status =
+            va_check(unsafe { bindings::vaUnmapBuffer(self.context.display().handle(), self.id) });
+
+        if status.is_err() {
+            error!("vaUnmapBuffer failed: {}", status.unwrap_err());
+        }
+
+        self.mapped.set(false);
+    }
+}
+
+/// Read-only mapping of a [`Buffer`], returned by [`Buffer::map`].
+pub struct MappedBuffer<'a, T: BufferElement> {
+    buffer: &'a Buffer,
+    data: &'a [T],
+}
+
+impl<'a, T: BufferElement> Deref for MappedBuffer<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.data
+    }
+}
+
+impl<'a, T: BufferElement> Drop for MappedBuffer<'a, T> {
+    fn drop(&mut self) {
+        self.buffer.unmap();
+    }
+}
+
+/// Read-write mapping of a [`Buffer`], returned by [`Buffer::map_mut`].
+pub struct MappedBufferMut<'a, T: BufferElement> {
+    buffer: &'a Buffer,
+    data: &'a mut [T],
+}
+
+impl<'a, T: BufferElement> Deref for MappedBufferMut<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.data
+    }
+}
+
+impl<'a, T: BufferElement> DerefMut for MappedBufferMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.data
+    }
+}
+
+impl<'a, T: BufferElement> Drop for MappedBufferMut<'a, T> {
+    fn drop(&mut self) {
+        self.buffer.unmap();
+    }
+}
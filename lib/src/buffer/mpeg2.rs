@@ -94,7 +94,7 @@ impl PictureParameterBufferMPEG2 {
 }
 
 /// Wrapper over the `VASliceParameterBufferMPEG2` FFI type.
-pub struct SliceParameterBufferMPEG2(Box<bindings::VASliceParameterBufferMPEG2>);
+pub struct SliceParameterBufferMPEG2(Vec<bindings::VASliceParameterBufferMPEG2>);
 
 impl SliceParameterBufferMPEG2 {
     /// Creates the wrapper.
@@ -109,7 +109,7 @@ impl SliceParameterBufferMPEG2 {
         quantiser_scale_code: i32,
         intra_slice_flag: i32,
     ) -> Self {
-        Self(Box::new(bindings::VASliceParameterBufferMPEG2 {
+        Self(vec![bindings::VASliceParameterBufferMPEG2 {
             slice_data_size,
             slice_data_offset,
             slice_data_flag,
@@ -119,16 +119,22 @@ impl SliceParameterBufferMPEG2 {
             quantiser_scale_code,
             intra_slice_flag,
             va_reserved: Default::default(),
-        }))
+        }])
     }
 
-    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VASliceParameterBufferMPEG2 {
-        self.0.as_mut()
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferMPEG2> {
+        &mut self.0
     }
 
     /// Returns the inner FFI type. Useful for testing purposes.
-    pub fn inner(&self) -> &bindings::VASliceParameterBufferMPEG2 {
-        self.0.as_ref()
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferMPEG2> {
+        &self.0
     }
 }
 
@@ -170,3 +176,361 @@ impl IQMatrixBufferMPEG2 {
         self.0.as_ref()
     }
 }
+
+/// Wrapper over the `sequence_extension` bindgen field in `VAEncSequenceParameterBufferMPEG2`.
+pub struct MPEG2EncSequenceExtension(bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_1);
+
+impl MPEG2EncSequenceExtension {
+    /// Creates the bindgen field.
+    pub fn new(
+        profile_and_level_indication: u32,
+        progressive_sequence: u32,
+        chroma_format: u32,
+        low_delay: u32,
+        frame_rate_extension_n: u32,
+        frame_rate_extension_d: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_1__bindgen_ty_1::new_bitfield_1(
+                profile_and_level_indication,
+                progressive_sequence,
+                chroma_format,
+                low_delay,
+                frame_rate_extension_n,
+                frame_rate_extension_d,
+            );
+
+        Self(bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_1 {
+            bits: bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_1__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_1 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `gop_header` bindgen field in `VAEncSequenceParameterBufferMPEG2`.
+pub struct MPEG2EncGopHeader(bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_2);
+
+impl MPEG2EncGopHeader {
+    /// Creates the bindgen field.
+    pub fn new(time_code: u32, closed_gop: u32, broken_link: u32) -> Self {
+        let _bitfield_1 =
+            bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_2__bindgen_ty_1::new_bitfield_1(
+                time_code,
+                closed_gop,
+                broken_link,
+            );
+
+        Self(bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_2 {
+            bits: bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_2__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAEncSequenceParameterBufferMPEG2__bindgen_ty_2 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `VAEncSequenceParameterBufferMPEG2` FFI type.
+///
+/// libva has no misc parameters for the MPEG-2 sequence extension and GOP header: they are part of
+/// this buffer, through [`MPEG2EncSequenceExtension`] and [`MPEG2EncGopHeader`]. The sequence
+/// display extension is the only MPEG-2 misc parameter, and is provided by
+/// [`EncMiscParameterExtensionDataSeqDisplayMPEG2`](crate::EncMiscParameterExtensionDataSeqDisplayMPEG2).
+pub struct EncSequenceParameterBufferMPEG2(Box<bindings::VAEncSequenceParameterBufferMPEG2>);
+
+impl EncSequenceParameterBufferMPEG2 {
+    /// Creates the wrapper.
+    ///
+    /// `new_gop_header` indicates whether a GOP header described by `gop_header` must be inserted
+    /// in the stream before the next picture.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        intra_period: u32,
+        ip_period: u32,
+        picture_width: u16,
+        picture_height: u16,
+        bits_per_second: u32,
+        frame_rate: f32,
+        aspect_ratio_information: u16,
+        vbv_buffer_size: u32,
+        sequence_extension: &MPEG2EncSequenceExtension,
+        new_gop_header: u32,
+        gop_header: &MPEG2EncGopHeader,
+    ) -> Self {
+        let sequence_extension = sequence_extension.0;
+        let gop_header = gop_header.0;
+
+        Self(Box::new(bindings::VAEncSequenceParameterBufferMPEG2 {
+            intra_period,
+            ip_period,
+            picture_width,
+            picture_height,
+            bits_per_second,
+            frame_rate,
+            aspect_ratio_information,
+            vbv_buffer_size,
+            sequence_extension,
+            new_gop_header,
+            gop_header,
+            va_reserved: Default::default(),
+        }))
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAEncSequenceParameterBufferMPEG2 {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAEncSequenceParameterBufferMPEG2 {
+        self.0.as_ref()
+    }
+}
+
+/// Wrapper over the `picture_coding_extension` bindgen field in
+/// `VAEncPictureParameterBufferMPEG2`.
+pub struct MPEG2EncPictureCodingExtension(
+    bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_1,
+);
+
+impl MPEG2EncPictureCodingExtension {
+    /// Creates the bindgen field.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        intra_dc_precision: u32,
+        picture_structure: u32,
+        top_field_first: u32,
+        frame_pred_frame_dct: u32,
+        concealment_motion_vectors: u32,
+        q_scale_type: u32,
+        intra_vlc_format: u32,
+        alternate_scan: u32,
+        repeat_first_field: u32,
+        progressive_frame: u32,
+        composite_display_flag: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_1__bindgen_ty_1::new_bitfield_1(
+                intra_dc_precision,
+                picture_structure,
+                top_field_first,
+                frame_pred_frame_dct,
+                concealment_motion_vectors,
+                q_scale_type,
+                intra_vlc_format,
+                alternate_scan,
+                repeat_first_field,
+                progressive_frame,
+                composite_display_flag,
+            );
+
+        Self(bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_1 {
+            bits: bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_1__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_1 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `composite_display` bindgen field in `VAEncPictureParameterBufferMPEG2`.
+pub struct MPEG2EncCompositeDisplay(bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_2);
+
+impl MPEG2EncCompositeDisplay {
+    /// Creates the bindgen field.
+    pub fn new(
+        v_axis: u32,
+        field_sequence: u32,
+        sub_carrier: u32,
+        burst_amplitude: u32,
+        sub_carrier_phase: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_2__bindgen_ty_1::new_bitfield_1(
+                v_axis,
+                field_sequence,
+                sub_carrier,
+                burst_amplitude,
+                sub_carrier_phase,
+            );
+
+        Self(bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_2 {
+            bits: bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_2__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAEncPictureParameterBufferMPEG2__bindgen_ty_2 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `VAEncPictureParameterBufferMPEG2` FFI type.
+pub struct EncPictureParameterBufferMPEG2(Box<bindings::VAEncPictureParameterBufferMPEG2>);
+
+impl EncPictureParameterBufferMPEG2 {
+    /// Creates the wrapper.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        reconstructed_picture: bindings::VASurfaceID,
+        forward_reference_picture: bindings::VASurfaceID,
+        backward_reference_picture: bindings::VASurfaceID,
+        coded_buf: bindings::VABufferID,
+        last_picture: u8,
+        picture_type: bindings::VAEncPictureType::Type,
+        temporal_reference: u32,
+        vbv_delay: u32,
+        f_code: [[u8; 2usize]; 2usize],
+        picture_coding_extension: &MPEG2EncPictureCodingExtension,
+        composite_display: &MPEG2EncCompositeDisplay,
+    ) -> Self {
+        let picture_coding_extension = picture_coding_extension.0;
+        let composite_display = composite_display.0;
+
+        Self(Box::new(bindings::VAEncPictureParameterBufferMPEG2 {
+            reconstructed_picture,
+            forward_reference_picture,
+            backward_reference_picture,
+            coded_buf,
+            last_picture,
+            picture_type,
+            temporal_reference,
+            vbv_delay,
+            f_code,
+            picture_coding_extension,
+            composite_display,
+            va_reserved: Default::default(),
+        }))
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAEncPictureParameterBufferMPEG2 {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAEncPictureParameterBufferMPEG2 {
+        self.0.as_ref()
+    }
+}
+
+/// Wrapper over the `VAEncSliceParameterBufferMPEG2` FFI type.
+pub struct EncSliceParameterBufferMPEG2(Box<bindings::VAEncSliceParameterBufferMPEG2>);
+
+impl EncSliceParameterBufferMPEG2 {
+    /// Creates the wrapper.
+    pub fn new(
+        macroblock_address: u32,
+        num_macroblocks: u32,
+        quantiser_scale_code: i32,
+        is_intra_slice: i32,
+    ) -> Self {
+        Self(Box::new(bindings::VAEncSliceParameterBufferMPEG2 {
+            macroblock_address,
+            num_macroblocks,
+            quantiser_scale_code,
+            is_intra_slice,
+            va_reserved: Default::default(),
+        }))
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAEncSliceParameterBufferMPEG2 {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAEncSliceParameterBufferMPEG2 {
+        self.0.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enc_sequence_bitfields() {
+        let sequence_extension = MPEG2EncSequenceExtension::new(0x48, 1, 1, 0, 2, 3);
+        // Safe because all bit patterns are valid for this union of integers.
+        let (bits, value) = unsafe {
+            (
+                sequence_extension.inner().bits,
+                sequence_extension.inner().value,
+            )
+        };
+        assert_eq!(bits.profile_and_level_indication(), 0x48);
+        assert_eq!(bits.progressive_sequence(), 1);
+        assert_eq!(bits.chroma_format(), 1);
+        assert_eq!(bits.low_delay(), 0);
+        assert_eq!(bits.frame_rate_extension_n(), 2);
+        assert_eq!(bits.frame_rate_extension_d(), 3);
+        assert_eq!(value, 0x48 | 1 << 8 | 1 << 9 | 2 << 12 | 3 << 14);
+
+        let gop_header = MPEG2EncGopHeader::new(0x1234, 1, 0);
+        // Safe because all bit patterns are valid for this union of integers.
+        let (bits, value) = unsafe { (gop_header.inner().bits, gop_header.inner().value) };
+        assert_eq!(bits.time_code(), 0x1234);
+        assert_eq!(bits.closed_gop(), 1);
+        assert_eq!(bits.broken_link(), 0);
+        assert_eq!(value, 0x1234 | 1 << 25);
+    }
+
+    #[test]
+    fn enc_picture_bitfields() {
+        let coding_extension = MPEG2EncPictureCodingExtension::new(2, 3, 1, 0, 1, 0, 1, 0, 0, 1, 0);
+        // Safe because all bit patterns are valid for this union of integers.
+        let (bits, value) = unsafe {
+            (
+                coding_extension.inner().bits,
+                coding_extension.inner().value,
+            )
+        };
+        assert_eq!(bits.intra_dc_precision(), 2);
+        assert_eq!(bits.picture_structure(), 3);
+        assert_eq!(bits.top_field_first(), 1);
+        assert_eq!(bits.frame_pred_frame_dct(), 0);
+        assert_eq!(bits.concealment_motion_vectors(), 1);
+        assert_eq!(bits.q_scale_type(), 0);
+        assert_eq!(bits.intra_vlc_format(), 1);
+        assert_eq!(bits.alternate_scan(), 0);
+        assert_eq!(bits.repeat_first_field(), 0);
+        assert_eq!(bits.progressive_frame(), 1);
+        assert_eq!(bits.composite_display_flag(), 0);
+        assert_eq!(value, 2 | 3 << 2 | 1 << 4 | 1 << 6 | 1 << 8 | 1 << 11);
+
+        let composite_display = MPEG2EncCompositeDisplay::new(1, 5, 0, 0x55, 0xaa);
+        // Safe because all bit patterns are valid for this union of integers.
+        let (bits, value) = unsafe {
+            (
+                composite_display.inner().bits,
+                composite_display.inner().value,
+            )
+        };
+        assert_eq!(bits.v_axis(), 1);
+        assert_eq!(bits.field_sequence(), 5);
+        assert_eq!(bits.sub_carrier(), 0);
+        assert_eq!(bits.burst_amplitude(), 0x55);
+        assert_eq!(bits.sub_carrier_phase(), 0xaa);
+        assert_eq!(value, 1 | 5 << 1 | 0x55 << 5 | 0xaa << 12);
+    }
+}
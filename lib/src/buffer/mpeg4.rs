@@ -0,0 +1,227 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Wrappers around MPEG4 Part 2 and H.263 `VABuffer` types.
+
+use crate::bindings;
+
+/// Wrapper over the `vol_fields` bindgen field in `VAPictureParameterBufferMPEG4`.
+pub struct MPEG4VolFields(bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_1);
+
+impl MPEG4VolFields {
+    /// Creates the bindgen field.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        short_video_header: u32,
+        chroma_format: u32,
+        interlaced: u32,
+        obmc_disable: u32,
+        sprite_enable: u32,
+        sprite_warping_accuracy: u32,
+        quant_type: u32,
+        quarter_sample: u32,
+        data_partitioned: u32,
+        reversible_vlc: u32,
+        resync_marker_disable: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_1__bindgen_ty_1::new_bitfield_1(
+                short_video_header,
+                chroma_format,
+                interlaced,
+                obmc_disable,
+                sprite_enable,
+                sprite_warping_accuracy,
+                quant_type,
+                quarter_sample,
+                data_partitioned,
+                reversible_vlc,
+                resync_marker_disable,
+            );
+
+        Self(bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_1 {
+            bits: bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_1__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_1 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `vop_fields` bindgen field in `VAPictureParameterBufferMPEG4`.
+pub struct MPEG4VopFields(bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_2);
+
+impl MPEG4VopFields {
+    /// Creates the bindgen field.
+    pub fn new(
+        vop_coding_type: u32,
+        backward_reference_vop_coding_type: u32,
+        vop_rounding_type: u32,
+        intra_dc_vlc_thr: u32,
+        top_field_first: u32,
+        alternate_vertical_scan_flag: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_2__bindgen_ty_1::new_bitfield_1(
+                vop_coding_type,
+                backward_reference_vop_coding_type,
+                vop_rounding_type,
+                intra_dc_vlc_thr,
+                top_field_first,
+                alternate_vertical_scan_flag,
+            );
+
+        Self(bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_2 {
+            bits: bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_2__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferMPEG4__bindgen_ty_2 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `VAPictureParameterBufferMPEG4` FFI type.
+///
+/// H.263 streams are decoded with `short_video_header` set in the [`MPEG4VolFields`], in which
+/// case `num_gobs_in_vop` and `num_macroblocks_in_gob` must be provided.
+pub struct PictureParameterBufferMPEG4(Box<bindings::VAPictureParameterBufferMPEG4>);
+
+impl PictureParameterBufferMPEG4 {
+    /// Creates the wrapper.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        vop_width: u16,
+        vop_height: u16,
+        forward_reference_picture: bindings::VASurfaceID,
+        backward_reference_picture: bindings::VASurfaceID,
+        vol_fields: &MPEG4VolFields,
+        no_of_sprite_warping_points: u8,
+        sprite_trajectory_du: [i16; 3usize],
+        sprite_trajectory_dv: [i16; 3usize],
+        quant_precision: u8,
+        vop_fields: &MPEG4VopFields,
+        vop_fcode_forward: u8,
+        vop_fcode_backward: u8,
+        vop_time_increment_resolution: u16,
+        num_gobs_in_vop: u8,
+        num_macroblocks_in_gob: u8,
+        trb: i16,
+        trd: i16,
+    ) -> Self {
+        let vol_fields = vol_fields.0;
+        let vop_fields = vop_fields.0;
+
+        Self(Box::new(bindings::VAPictureParameterBufferMPEG4 {
+            vop_width,
+            vop_height,
+            forward_reference_picture,
+            backward_reference_picture,
+            vol_fields,
+            no_of_sprite_warping_points,
+            sprite_trajectory_du,
+            sprite_trajectory_dv,
+            quant_precision,
+            vop_fields,
+            vop_fcode_forward,
+            vop_fcode_backward,
+            vop_time_increment_resolution,
+            num_gobs_in_vop,
+            num_macroblocks_in_gob,
+            TRB: trb,
+            TRD: trd,
+            va_reserved: Default::default(),
+        }))
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAPictureParameterBufferMPEG4 {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAPictureParameterBufferMPEG4 {
+        self.0.as_ref()
+    }
+}
+
+/// Wrapper over the `VASliceParameterBufferMPEG4` FFI type.
+pub struct SliceParameterBufferMPEG4(Vec<bindings::VASliceParameterBufferMPEG4>);
+
+impl SliceParameterBufferMPEG4 {
+    /// Creates the wrapper.
+    pub fn new(
+        slice_data_size: u32,
+        slice_data_offset: u32,
+        slice_data_flag: u32,
+        macroblock_offset: u32,
+        macroblock_number: u32,
+        quant_scale: i32,
+    ) -> Self {
+        Self(vec![bindings::VASliceParameterBufferMPEG4 {
+            slice_data_size,
+            slice_data_offset,
+            slice_data_flag,
+            macroblock_offset,
+            macroblock_number,
+            quant_scale,
+            va_reserved: Default::default(),
+        }])
+    }
+
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferMPEG4> {
+        &mut self.0
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferMPEG4> {
+        &self.0
+    }
+}
+
+/// Wrapper over the `VAIQMatrixBufferMPEG4` FFI type.
+pub struct IQMatrixBufferMPEG4(Box<bindings::VAIQMatrixBufferMPEG4>);
+
+impl IQMatrixBufferMPEG4 {
+    /// Creates the wrapper.
+    pub fn new(
+        load_intra_quant_mat: i32,
+        load_non_intra_quant_mat: i32,
+        intra_quant_mat: [u8; 64usize],
+        non_intra_quant_mat: [u8; 64usize],
+    ) -> Self {
+        Self(Box::new(bindings::VAIQMatrixBufferMPEG4 {
+            load_intra_quant_mat,
+            load_non_intra_quant_mat,
+            intra_quant_mat,
+            non_intra_quant_mat,
+            va_reserved: Default::default(),
+        }))
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAIQMatrixBufferMPEG4 {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAIQMatrixBufferMPEG4 {
+        self.0.as_ref()
+    }
+}
@@ -0,0 +1,617 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Wrappers around VC-1 `VABuffer` types.
+
+use crate::bindings;
+
+/// Wrapper over the `sequence_fields` bindgen field in `VAPictureParameterBufferVC1`.
+pub struct VC1SequenceFields(bindings::_VAPictureParameterBufferVC1__bindgen_ty_1);
+
+impl VC1SequenceFields {
+    /// Creates the bindgen field.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        pulldown: u32,
+        interlace: u32,
+        tfcntrflag: u32,
+        finterpflag: u32,
+        psf: u32,
+        multires: u32,
+        overlap: u32,
+        syncmarker: u32,
+        rangered: u32,
+        max_b_frames: u32,
+        profile: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_1__bindgen_ty_1::new_bitfield_1(
+                pulldown,
+                interlace,
+                tfcntrflag,
+                finterpflag,
+                psf,
+                multires,
+                overlap,
+                syncmarker,
+                rangered,
+                max_b_frames,
+                profile,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_1 {
+            bits: bindings::_VAPictureParameterBufferVC1__bindgen_ty_1__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_1 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `entrypoint_fields` bindgen field in `VAPictureParameterBufferVC1`.
+pub struct VC1EntrypointFields(bindings::_VAPictureParameterBufferVC1__bindgen_ty_2);
+
+impl VC1EntrypointFields {
+    /// Creates the bindgen field.
+    pub fn new(broken_link: u32, closed_entry: u32, panscan_flag: u32, loopfilter: u32) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_2__bindgen_ty_1::new_bitfield_1(
+                broken_link,
+                closed_entry,
+                panscan_flag,
+                loopfilter,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_2 {
+            bits: bindings::_VAPictureParameterBufferVC1__bindgen_ty_2__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_2 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `range_mapping_fields` bindgen field in `VAPictureParameterBufferVC1`.
+pub struct VC1RangeMappingFields(bindings::_VAPictureParameterBufferVC1__bindgen_ty_3);
+
+impl VC1RangeMappingFields {
+    /// Creates the bindgen field.
+    pub fn new(luma_flag: u32, luma: u32, chroma_flag: u32, chroma: u32) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_3__bindgen_ty_1::new_bitfield_1(
+                luma_flag,
+                luma,
+                chroma_flag,
+                chroma,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_3 {
+            bits: bindings::_VAPictureParameterBufferVC1__bindgen_ty_3__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_3 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `picture_fields` bindgen field in `VAPictureParameterBufferVC1`.
+pub struct VC1PictureFields(bindings::_VAPictureParameterBufferVC1__bindgen_ty_4);
+
+impl VC1PictureFields {
+    /// Creates the bindgen field.
+    pub fn new(
+        picture_type: u32,
+        frame_coding_mode: u32,
+        top_field_first: u32,
+        is_first_field: u32,
+        intensity_compensation: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_4__bindgen_ty_1::new_bitfield_1(
+                picture_type,
+                frame_coding_mode,
+                top_field_first,
+                is_first_field,
+                intensity_compensation,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_4 {
+            bits: bindings::_VAPictureParameterBufferVC1__bindgen_ty_4__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_4 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `raw_coding` bindgen field in `VAPictureParameterBufferVC1`, i.e. which
+/// bitplanes are coded in raw mode at the macroblock level.
+pub struct VC1RawCoding(bindings::_VAPictureParameterBufferVC1__bindgen_ty_5);
+
+impl VC1RawCoding {
+    /// Creates the bindgen field.
+    pub fn new(
+        mv_type_mb: u32,
+        direct_mb: u32,
+        skip_mb: u32,
+        field_tx: u32,
+        forward_mb: u32,
+        ac_pred: u32,
+        overflags: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_5__bindgen_ty_1::new_bitfield_1(
+                mv_type_mb, direct_mb, skip_mb, field_tx, forward_mb, ac_pred, overflags,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_5 {
+            flags: bindings::_VAPictureParameterBufferVC1__bindgen_ty_5__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_5 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `bitplane_present` bindgen field in `VAPictureParameterBufferVC1`, i.e. which
+/// bitplanes are sent in the [`BitPlaneBufferVC1`].
+pub struct VC1BitplanePresent(bindings::_VAPictureParameterBufferVC1__bindgen_ty_6);
+
+impl VC1BitplanePresent {
+    /// Creates the bindgen field.
+    pub fn new(
+        bp_mv_type_mb: u32,
+        bp_direct_mb: u32,
+        bp_skip_mb: u32,
+        bp_field_tx: u32,
+        bp_forward_mb: u32,
+        bp_ac_pred: u32,
+        bp_overflags: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_6__bindgen_ty_1::new_bitfield_1(
+                bp_mv_type_mb,
+                bp_direct_mb,
+                bp_skip_mb,
+                bp_field_tx,
+                bp_forward_mb,
+                bp_ac_pred,
+                bp_overflags,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_6 {
+            flags: bindings::_VAPictureParameterBufferVC1__bindgen_ty_6__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_6 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `reference_fields` bindgen field in `VAPictureParameterBufferVC1`.
+pub struct VC1ReferenceFields(bindings::_VAPictureParameterBufferVC1__bindgen_ty_7);
+
+impl VC1ReferenceFields {
+    /// Creates the bindgen field.
+    pub fn new(
+        reference_distance_flag: u32,
+        reference_distance: u32,
+        num_reference_pictures: u32,
+        reference_field_pic_indicator: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_7__bindgen_ty_1::new_bitfield_1(
+                reference_distance_flag,
+                reference_distance,
+                num_reference_pictures,
+                reference_field_pic_indicator,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_7 {
+            bits: bindings::_VAPictureParameterBufferVC1__bindgen_ty_7__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_7 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `mv_fields` bindgen field in `VAPictureParameterBufferVC1`.
+pub struct VC1MvFields(bindings::_VAPictureParameterBufferVC1__bindgen_ty_8);
+
+impl VC1MvFields {
+    /// Creates the bindgen field.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        mv_mode: u32,
+        mv_mode2: u32,
+        mv_table: u32,
+        two_mv_block_pattern_table: u32,
+        four_mv_switch: u32,
+        four_mv_block_pattern_table: u32,
+        extended_mv_flag: u32,
+        extended_mv_range: u32,
+        extended_dmv_flag: u32,
+        extended_dmv_range: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_8__bindgen_ty_1::new_bitfield_1(
+                mv_mode,
+                mv_mode2,
+                mv_table,
+                two_mv_block_pattern_table,
+                four_mv_switch,
+                four_mv_block_pattern_table,
+                extended_mv_flag,
+                extended_mv_range,
+                extended_dmv_flag,
+                extended_dmv_range,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_8 {
+            bits: bindings::_VAPictureParameterBufferVC1__bindgen_ty_8__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_8 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `pic_quantizer_fields` bindgen field in `VAPictureParameterBufferVC1`.
+pub struct VC1PicQuantizerFields(bindings::_VAPictureParameterBufferVC1__bindgen_ty_9);
+
+impl VC1PicQuantizerFields {
+    /// Creates the bindgen field.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        dquant: u32,
+        quantizer: u32,
+        half_qp: u32,
+        pic_quantizer_scale: u32,
+        pic_quantizer_type: u32,
+        dq_frame: u32,
+        dq_profile: u32,
+        dq_sb_edge: u32,
+        dq_db_edge: u32,
+        dq_binary_level: u32,
+        alt_pic_quantizer: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_9__bindgen_ty_1::new_bitfield_1(
+                dquant,
+                quantizer,
+                half_qp,
+                pic_quantizer_scale,
+                pic_quantizer_type,
+                dq_frame,
+                dq_profile,
+                dq_sb_edge,
+                dq_db_edge,
+                dq_binary_level,
+                alt_pic_quantizer,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_9 {
+            bits: bindings::_VAPictureParameterBufferVC1__bindgen_ty_9__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_9 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `transform_fields` bindgen field in `VAPictureParameterBufferVC1`.
+pub struct VC1TransformFields(bindings::_VAPictureParameterBufferVC1__bindgen_ty_10);
+
+impl VC1TransformFields {
+    /// Creates the bindgen field.
+    pub fn new(
+        variable_sized_transform_flag: u32,
+        mb_level_transform_type_flag: u32,
+        frame_level_transform_type: u32,
+        transform_ac_codingset_idx1: u32,
+        transform_ac_codingset_idx2: u32,
+        intra_transform_dc_table: u32,
+    ) -> Self {
+        let _bitfield_1 =
+            bindings::_VAPictureParameterBufferVC1__bindgen_ty_10__bindgen_ty_1::new_bitfield_1(
+                variable_sized_transform_flag,
+                mb_level_transform_type_flag,
+                frame_level_transform_type,
+                transform_ac_codingset_idx1,
+                transform_ac_codingset_idx2,
+                intra_transform_dc_table,
+            );
+
+        Self(bindings::_VAPictureParameterBufferVC1__bindgen_ty_10 {
+            bits: bindings::_VAPictureParameterBufferVC1__bindgen_ty_10__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+                __bindgen_padding_0: Default::default(),
+            },
+        })
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::_VAPictureParameterBufferVC1__bindgen_ty_10 {
+        &self.0
+    }
+}
+
+/// Wrapper over the `VAPictureParameterBufferVC1` FFI type.
+pub struct PictureParameterBufferVC1(Box<bindings::VAPictureParameterBufferVC1>);
+
+impl PictureParameterBufferVC1 {
+    /// Creates the wrapper.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        forward_reference_picture: bindings::VASurfaceID,
+        backward_reference_picture: bindings::VASurfaceID,
+        inloop_decoded_picture: bindings::VASurfaceID,
+        sequence_fields: &VC1SequenceFields,
+        coded_width: u16,
+        coded_height: u16,
+        entrypoint_fields: &VC1EntrypointFields,
+        conditional_overlap_flag: u8,
+        fast_uvmc_flag: u8,
+        range_mapping_fields: &VC1RangeMappingFields,
+        b_picture_fraction: u8,
+        cbp_table: u8,
+        mb_mode_table: u8,
+        range_reduction_frame: u8,
+        rounding_control: u8,
+        post_processing: u8,
+        picture_resolution_index: u8,
+        luma_scale: u8,
+        luma_shift: u8,
+        picture_fields: &VC1PictureFields,
+        raw_coding: &VC1RawCoding,
+        bitplane_present: &VC1BitplanePresent,
+        reference_fields: &VC1ReferenceFields,
+        mv_fields: &VC1MvFields,
+        pic_quantizer_fields: &VC1PicQuantizerFields,
+        transform_fields: &VC1TransformFields,
+        luma_scale2: u8,
+        luma_shift2: u8,
+        intensity_compensation_field: u8,
+    ) -> Self {
+        Self(Box::new(bindings::VAPictureParameterBufferVC1 {
+            forward_reference_picture,
+            backward_reference_picture,
+            inloop_decoded_picture,
+            sequence_fields: sequence_fields.0,
+            coded_width,
+            coded_height,
+            entrypoint_fields: entrypoint_fields.0,
+            conditional_overlap_flag,
+            fast_uvmc_flag,
+            range_mapping_fields: range_mapping_fields.0,
+            b_picture_fraction,
+            cbp_table,
+            mb_mode_table,
+            range_reduction_frame,
+            rounding_control,
+            post_processing,
+            picture_resolution_index,
+            luma_scale,
+            luma_shift,
+            picture_fields: picture_fields.0,
+            raw_coding: raw_coding.0,
+            bitplane_present: bitplane_present.0,
+            reference_fields: reference_fields.0,
+            mv_fields: mv_fields.0,
+            pic_quantizer_fields: pic_quantizer_fields.0,
+            transform_fields: transform_fields.0,
+            luma_scale2,
+            luma_shift2,
+            intensity_compensation_field,
+            va_reserved: Default::default(),
+        }))
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAPictureParameterBufferVC1 {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAPictureParameterBufferVC1 {
+        self.0.as_ref()
+    }
+}
+
+/// Wrapper over the `VASliceParameterBufferVC1` FFI type.
+pub struct SliceParameterBufferVC1(Vec<bindings::VASliceParameterBufferVC1>);
+
+impl SliceParameterBufferVC1 {
+    /// Creates the wrapper.
+    pub fn new(
+        slice_data_size: u32,
+        slice_data_offset: u32,
+        slice_data_flag: u32,
+        macroblock_offset: u32,
+        slice_vertical_position: u32,
+    ) -> Self {
+        Self(vec![bindings::VASliceParameterBufferVC1 {
+            slice_data_size,
+            slice_data_offset,
+            slice_data_flag,
+            macroblock_offset,
+            slice_vertical_position,
+            va_reserved: Default::default(),
+        }])
+    }
+
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferVC1> {
+        &mut self.0
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferVC1> {
+        &self.0
+    }
+}
+
+/// Content of a `VABitPlaneBufferType` buffer, holding the bitplanes of a VC-1 picture that are
+/// not coded in raw mode.
+///
+/// Each macroblock is described by a nibble holding up to three bitplanes, two macroblocks being
+/// packed per byte in raster order. The meaning of each bit depends on the picture type:
+///
+/// | Picture type | Bit 2     | Bit 1  | Bit 0    |
+/// |--------------|-----------|--------|----------|
+/// | I or BI      | OVERFLAGS | ACPRED | FIELDTX  |
+/// | P            | MVTYPEMB  | SKIPMB | DIRECTMB |
+/// | B            | FORWARDMB | SKIPMB | DIRECTMB |
+///
+/// The first macroblock of each pair goes into the upper nibble. This is the layout expected by
+/// drivers in practice, although the libva documentation describes the opposite order.
+pub struct BitPlaneBufferVC1(Vec<u8>);
+
+impl BitPlaneBufferVC1 {
+    /// Packs the bitplanes of `num_macroblocks` macroblocks. `planes[i]` contains the value of bit
+    /// `i` of each macroblock in raster order, any non-zero value setting the bit, or `None` if
+    /// the corresponding bitplane is not present.
+    ///
+    /// Missing values in a plane shorter than `num_macroblocks` are treated as zero.
+    pub fn new(num_macroblocks: usize, planes: [Option<&[u8]>; 3]) -> Self {
+        let nibble = |mb: usize| {
+            planes
+                .iter()
+                .enumerate()
+                .filter_map(|(bit, plane)| plane.map(|plane| (bit, plane)))
+                .filter(|(_, plane)| plane.get(mb).is_some_and(|&v| v != 0))
+                .fold(0u8, |nibble, (bit, _)| nibble | (1 << bit))
+        };
+
+        let data = (0..num_macroblocks.div_ceil(2))
+            .map(|i| {
+                let first = nibble(2 * i);
+                let second = match 2 * i + 1 {
+                    mb if mb < num_macroblocks => nibble(mb),
+                    _ => 0,
+                };
+
+                (first << 4) | second
+            })
+            .collect();
+
+        Self(data)
+    }
+
+    /// Packs the bitplanes of an I or BI picture.
+    pub fn new_intra(
+        num_macroblocks: usize,
+        field_tx: Option<&[u8]>,
+        ac_pred: Option<&[u8]>,
+        overflags: Option<&[u8]>,
+    ) -> Self {
+        Self::new(num_macroblocks, [field_tx, ac_pred, overflags])
+    }
+
+    /// Packs the bitplanes of a P picture.
+    pub fn new_p(
+        num_macroblocks: usize,
+        direct_mb: Option<&[u8]>,
+        skip_mb: Option<&[u8]>,
+        mv_type_mb: Option<&[u8]>,
+    ) -> Self {
+        Self::new(num_macroblocks, [direct_mb, skip_mb, mv_type_mb])
+    }
+
+    /// Packs the bitplanes of a B picture.
+    pub fn new_b(
+        num_macroblocks: usize,
+        direct_mb: Option<&[u8]>,
+        skip_mb: Option<&[u8]>,
+        forward_mb: Option<&[u8]>,
+    ) -> Self {
+        Self::new(num_macroblocks, [direct_mb, skip_mb, forward_mb])
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+
+    /// Returns the packed bitplanes.
+    pub fn inner(&self) -> &Vec<u8> {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_bitplanes() {
+        let skip_mb = [1, 0, 1];
+        let direct_mb = [0, 1, 1];
+
+        let bitplane = BitPlaneBufferVC1::new_p(3, Some(&direct_mb), Some(&skip_mb), None);
+        assert_eq!(*bitplane.inner(), [0x21, 0x30]);
+
+        let bitplane = BitPlaneBufferVC1::new_intra(2, None, None, Some(&[1]));
+        assert_eq!(*bitplane.inner(), [0x40]);
+    }
+}
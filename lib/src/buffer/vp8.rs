@@ -137,7 +137,7 @@ impl PictureParameterBufferVP8 {
 }
 
 /// Wrapper over the `VASliceParameterBufferVP8` FFI type.
-pub struct SliceParameterBufferVP8(Box<bindings::VASliceParameterBufferVP8>);
+pub struct SliceParameterBufferVP8(Vec<bindings::VASliceParameterBufferVP8>);
 
 impl SliceParameterBufferVP8 {
     /// Creates the wrapper.
@@ -149,7 +149,7 @@ impl SliceParameterBufferVP8 {
         num_of_partitions: u8,
         partition_size: [u32; 9usize],
     ) -> Self {
-        Self(Box::new(bindings::VASliceParameterBufferVP8 {
+        Self(vec![bindings::VASliceParameterBufferVP8 {
             slice_data_size,
             slice_data_offset,
             slice_data_flag,
@@ -157,16 +157,22 @@ impl SliceParameterBufferVP8 {
             num_of_partitions,
             partition_size,
             va_reserved: Default::default(),
-        }))
+        }])
     }
 
-    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VASliceParameterBufferVP8 {
-        self.0.as_mut()
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferVP8> {
+        &mut self.0
     }
 
     /// Returns the inner FFI type. Useful for testing purposes.
-    pub fn inner(&self) -> &bindings::VASliceParameterBufferVP8 {
-        self.0.as_ref()
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferVP8> {
+        &self.0
     }
 }
 
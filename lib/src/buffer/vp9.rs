@@ -189,7 +189,7 @@ impl SegmentParameterVP9 {
 }
 
 /// Wrapper over the `VASliceParameterBufferVP9` FFI type.
-pub struct SliceParameterBufferVP9(Box<bindings::VASliceParameterBufferVP9>);
+pub struct SliceParameterBufferVP9(Vec<bindings::VASliceParameterBufferVP9>);
 
 impl SliceParameterBufferVP9 {
     /// Creates the wrapper.
@@ -201,22 +201,28 @@ impl SliceParameterBufferVP9 {
     ) -> Self {
         let seg_param = seg_param.map(|param| param.0);
 
-        Self(Box::new(bindings::VASliceParameterBufferVP9 {
+        Self(vec![bindings::VASliceParameterBufferVP9 {
             slice_data_size,
             slice_data_offset,
             slice_data_flag,
             seg_param,
             va_reserved: Default::default(),
-        }))
+        }])
     }
 
-    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VASliceParameterBufferVP9 {
-        self.0.as_mut()
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferVP9> {
+        &mut self.0
     }
 
     /// Returns the inner FFI type. Useful for testing purposes.
-    pub fn inner(&self) -> &bindings::VASliceParameterBufferVP9 {
-        self.0.as_ref()
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferVP9> {
+        &self.0
     }
 }
 
@@ -0,0 +1,278 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Wrappers around VVC (H.266) `VABuffer` types, available with libva 1.22 and higher.
+//!
+//! The VVC parameter structures have far more fields than can reasonably be passed to a
+//! constructor, so the larger wrappers take the FFI structure filled by the caller instead.
+
+use crate::bindings;
+
+/// Wrapper over the `VAPictureVVC` FFI type.
+#[derive(Clone, Copy)]
+pub struct PictureVVC(bindings::VAPictureVVC);
+
+impl PictureVVC {
+    /// Creates the wrapper. `flags` is a combination of the `VA_PICTURE_VVC_*` flags.
+    pub fn new(picture_id: bindings::VASurfaceID, pic_order_cnt: i32, flags: u32) -> Self {
+        Self(bindings::VAPictureVVC {
+            picture_id,
+            pic_order_cnt,
+            flags,
+            va_reserved: Default::default(),
+        })
+    }
+
+    /// Returns an invalid picture, used to fill the unused entries of the reference lists.
+    pub fn invalid() -> Self {
+        Self::new(
+            bindings::VA_INVALID_SURFACE,
+            0,
+            bindings::VA_PICTURE_VVC_INVALID,
+        )
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAPictureVVC {
+        &self.0
+    }
+}
+
+/// Wrapper over the `VAPictureParameterBufferVVC` FFI type.
+pub struct PictureParameterBufferVVC(Box<bindings::VAPictureParameterBufferVVC>);
+
+impl PictureParameterBufferVVC {
+    /// Creates the wrapper from the FFI type filled by the caller.
+    pub fn new(params: bindings::VAPictureParameterBufferVVC) -> Self {
+        Self(Box::new(params))
+    }
+
+    /// Sets the picture being decoded.
+    pub fn set_curr_pic(&mut self, curr_pic: PictureVVC) {
+        self.0.CurrPic = curr_pic.0;
+    }
+
+    /// Sets the reference frames of the picture being decoded.
+    pub fn set_reference_frames(&mut self, reference_frames: [PictureVVC; 15usize]) {
+        self.0.ReferenceFrames = reference_frames.map(|pic| pic.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAPictureParameterBufferVVC {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAPictureParameterBufferVVC {
+        self.0.as_ref()
+    }
+}
+
+/// Wrapper over an array of the `VASliceParameterBufferVVC` FFI type, describing all the slices
+/// of a picture in a single buffer.
+#[derive(Default)]
+pub struct SliceParameterBufferVVC(Vec<bindings::VASliceParameterBufferVVC>);
+
+impl SliceParameterBufferVVC {
+    /// Creates an empty array of slice parameters.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds the parameters of a slice, filled by the caller, to the array.
+    pub fn add_slice_parameter(&mut self, params: bindings::VASliceParameterBufferVVC) {
+        self.0.push(params);
+    }
+
+    /// Appends the slices of `other` to this buffer, so that all the slices of a picture can be
+    /// submitted through a single buffer.
+    pub fn add_slice(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceParameterBufferVVC> {
+        &mut self.0
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &Vec<bindings::VASliceParameterBufferVVC> {
+        &self.0
+    }
+}
+
+/// Wrapper over the `VAScalingListVVC` FFI type, sent as a `VAIQMatrixBufferType` buffer.
+pub struct ScalingListVVC(Box<bindings::VAScalingListVVC>);
+
+impl ScalingListVVC {
+    /// Creates the wrapper from the FFI type filled by the caller.
+    pub fn new(scaling_list: bindings::VAScalingListVVC) -> Self {
+        Self(Box::new(scaling_list))
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VAScalingListVVC {
+        self.0.as_mut()
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &bindings::VAScalingListVVC {
+        self.0.as_ref()
+    }
+}
+
+/// Wrapper over an array of the `VAAlfDataVVC` FFI type, sent as a `VAAlfBufferType` buffer.
+///
+/// The array contains one entry per adaptive loop filter APS referenced by the picture.
+#[derive(Default)]
+pub struct AlfBufferVVC(Vec<bindings::VAAlfDataVVC>);
+
+impl AlfBufferVVC {
+    /// Creates an empty array of ALF parameters.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds the ALF parameters of an APS, filled by the caller, to the array.
+    pub fn add_aps(&mut self, alf_data: bindings::VAAlfDataVVC) {
+        self.0.push(alf_data);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VAAlfDataVVC> {
+        &mut self.0
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &Vec<bindings::VAAlfDataVVC> {
+        &self.0
+    }
+}
+
+/// Wrapper over an array of the `VALmcsDataVVC` FFI type, sent as a `VALmcsBufferType` buffer.
+///
+/// The array contains one entry per luma mapping with chroma scaling APS referenced by the
+/// picture.
+#[derive(Default)]
+pub struct LmcsBufferVVC(Vec<bindings::VALmcsDataVVC>);
+
+impl LmcsBufferVVC {
+    /// Creates an empty array of LMCS parameters.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds the LMCS parameters of an APS, filled by the caller, to the array.
+    pub fn add_aps(&mut self, lmcs_data: bindings::VALmcsDataVVC) {
+        self.0.push(lmcs_data);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VALmcsDataVVC> {
+        &mut self.0
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &Vec<bindings::VALmcsDataVVC> {
+        &self.0
+    }
+}
+
+/// Wrapper over an array of the `VASubPicVVC` FFI type, sent as a `VASubPicBufferType` buffer.
+///
+/// The array contains one entry per subpicture of the sequence.
+#[derive(Default)]
+pub struct SubPicBufferVVC(Vec<bindings::VASubPicVVC>);
+
+impl SubPicBufferVVC {
+    /// Creates an empty array of subpictures.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a subpicture, filled by the caller, to the array.
+    pub fn add_subpic(&mut self, subpic: bindings::VASubPicVVC) {
+        self.0.push(subpic);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASubPicVVC> {
+        &mut self.0
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &Vec<bindings::VASubPicVVC> {
+        &self.0
+    }
+}
+
+/// Wrapper over an array of the `VATileBufferVVC` FFI type, sent as a `VATileBufferType` buffer.
+pub struct TileBufferVVC(Vec<bindings::VATileBufferVVC>);
+
+impl TileBufferVVC {
+    /// Creates the wrapper from the `pps_tile_column_width_minus1` and
+    /// `pps_tile_row_height_minus1` syntax elements of the PPS. The driver expects the column
+    /// widths followed by the row heights.
+    pub fn new(tile_column_width_minus1: &[u16], tile_row_height_minus1: &[u16]) -> Self {
+        Self(
+            tile_column_width_minus1
+                .iter()
+                .chain(tile_row_height_minus1)
+                .map(|&tile_dimension| bindings::VATileBufferVVC {
+                    tile_dimension,
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VATileBufferVVC> {
+        &mut self.0
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &Vec<bindings::VATileBufferVVC> {
+        &self.0
+    }
+}
+
+/// Wrapper over an array of the `VASliceStructVVC` FFI type, sent as a `VASliceStructBufferType`
+/// buffer.
+///
+/// The array describes the rectangular slices of the picture, as signaled in the PPS.
+#[derive(Default)]
+pub struct SliceStructBufferVVC(Vec<bindings::VASliceStructVVC>);
+
+impl SliceStructBufferVVC {
+    /// Creates an empty array of slice structures.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a slice structure, filled by the caller, to the array.
+    pub fn add_slice_struct(&mut self, slice_struct: bindings::VASliceStructVVC) {
+        self.0.push(slice_struct);
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Vec<bindings::VASliceStructVVC> {
+        &mut self.0
+    }
+
+    /// Returns the inner FFI type. Useful for testing purposes.
+    pub fn inner(&self) -> &Vec<bindings::VASliceStructVVC> {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_dimensions_order() {
+        let tiles = TileBufferVVC::new(&[3, 7], &[1, 2, 5]);
+
+        let dimensions: Vec<_> = tiles
+            .inner()
+            .iter()
+            .map(|tile| tile.tile_dimension)
+            .collect();
+        // Column widths come first, followed by row heights.
+        assert_eq!(dimensions, [3, 7, 1, 2, 5]);
+    }
+}
@@ -0,0 +1,496 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Frame checksums and dumps for conformance testing.
+//!
+//! Conformance suites (Fluster, JVT, Argon...) provide per-frame MD5 or CRC32 checksums computed
+//! on the visible area of each decoded frame, laid out as planar YUV: the whole luma plane,
+//! followed by the whole first and second chroma planes. 8-bit samples take one byte, and higher
+//! bit depths are stored as 16-bit little-endian values holding the sample in their low bits.
+//!
+//! The functions of this module compute these checksums, and write frames to raw YUV or Y4M
+//! files with the same layout, from anything implementing [`ImageData`].
+
+use std::io::Write;
+
+use thiserror::Error;
+
+use crate::bindings;
+use crate::convert::plane_rows;
+use crate::convert::plane_sizes;
+use crate::convert::ConvertError;
+use crate::ImageData;
+
+/// Error type for the dump functions.
+#[derive(Debug, Error)]
+pub enum DumpError {
+    #[error("I/O error while writing frame: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("error while reading image: {0}")]
+    ConvertError(#[from] ConvertError),
+    #[error("fourcc {0:#010x} cannot be stored in a Y4M stream")]
+    UnsupportedY4mFormat(u32),
+    #[error("frame does not match the format or size of the stream")]
+    FrameMismatch,
+}
+
+/// Calls `f` with each row of the canonical planar representation of the `visible` area of
+/// `image`.
+fn for_each_row<S, E, F>(image: &S, visible: (u32, u32), mut f: F) -> Result<(), E>
+where
+    S: ImageData + ?Sized,
+    E: From<ConvertError>,
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    let va_image = image.va_image();
+    let data = image.data();
+    let fourcc = va_image.format.fourcc;
+    let width = std::cmp::min(visible.0, u32::from(va_image.width)) as usize;
+    let height = std::cmp::min(visible.1, u32::from(va_image.height)) as usize;
+    let sizes =
+        plane_sizes(fourcc, width, height).ok_or(ConvertError::UnsupportedFormat(fourcc))?;
+    let rows = |plane: usize| plane_rows(va_image, data, plane, sizes[plane].0, sizes[plane].1);
+
+    // Converts P010 samples, which hold their value in the 10 upper bits, into canonical ones.
+    let samples16 = |bytes: &mut dyn Iterator<Item = &[u8]>| -> Vec<u8> {
+        bytes
+            .flat_map(|s| (u16::from_le_bytes([s[0], s[1]]) >> 6).to_le_bytes())
+            .collect()
+    };
+
+    match fourcc {
+        bindings::VA_FOURCC_NV12 => {
+            for row in rows(0)? {
+                f(row)?;
+            }
+            let uv = rows(1)?;
+            for component in 0..2 {
+                for row in &uv {
+                    f(&row
+                        .iter()
+                        .skip(component)
+                        .step_by(2)
+                        .copied()
+                        .collect::<Vec<_>>())?;
+                }
+            }
+        }
+        bindings::VA_FOURCC_I420 | bindings::VA_FOURCC_YV12 | bindings::VA_FOURCC_RGBA => {
+            let planes: &[usize] = match fourcc {
+                bindings::VA_FOURCC_I420 => &[0, 1, 2],
+                bindings::VA_FOURCC_YV12 => &[0, 2, 1],
+                _ => &[0],
+            };
+
+            for &plane in planes {
+                for row in rows(plane)? {
+                    f(row)?;
+                }
+            }
+        }
+        bindings::VA_FOURCC_P010 => {
+            for row in rows(0)? {
+                f(&samples16(&mut row.chunks_exact(2)))?;
+            }
+            let uv = rows(1)?;
+            for component in 0..2 {
+                for row in &uv {
+                    f(&samples16(
+                        &mut row.chunks_exact(4).map(|uv| &uv[component * 2..]),
+                    ))?;
+                }
+            }
+        }
+        bindings::VA_FOURCC_YUY2 => {
+            let yuyv = rows(0)?;
+            for row in &yuyv {
+                f(&row
+                    .iter()
+                    .step_by(2)
+                    .take(width)
+                    .copied()
+                    .collect::<Vec<_>>())?;
+            }
+            for component in [1, 3] {
+                for row in &yuyv {
+                    f(&row
+                        .chunks_exact(4)
+                        .map(|yuyv| yuyv[component])
+                        .collect::<Vec<_>>())?;
+                }
+            }
+        }
+        _ => return Err(ConvertError::UnsupportedFormat(fourcc).into()),
+    }
+
+    Ok(())
+}
+
+const MD5_SHIFTS: [[u32; 4]; 4] = [
+    [7, 12, 17, 22],
+    [5, 9, 14, 20],
+    [4, 11, 16, 23],
+    [6, 10, 15, 21],
+];
+
+const MD5_CONSTANTS: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+/// An MD5 digest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Md5Digest([u8; 16]);
+
+impl Md5Digest {
+    /// Returns the bytes of the digest.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+/// Formats the digest as a lowercase hexadecimal string, as found in conformance suites.
+impl std::fmt::Display for Md5Digest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for b in self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Incremental MD5 hasher.
+pub struct Md5 {
+    state: [u32; 4],
+    block: [u8; 64],
+    block_len: usize,
+    length: u64,
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Md5 {
+    /// Creates a new hasher.
+    pub fn new() -> Self {
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            block: [0; 64],
+            block_len: 0,
+            length: 0,
+        }
+    }
+
+    /// Adds `data` to the hashed content.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        while !data.is_empty() {
+            let len = std::cmp::min(64 - self.block_len, data.len());
+            self.block[self.block_len..self.block_len + len].copy_from_slice(&data[..len]);
+            self.block_len += len;
+            data = &data[len..];
+
+            if self.block_len == 64 {
+                self.process_block();
+                self.block_len = 0;
+            }
+        }
+    }
+
+    /// Returns the digest of the hashed content.
+    pub fn finalize(mut self) -> Md5Digest {
+        let bit_length = self.length.wrapping_mul(8);
+
+        self.update(&[0x80]);
+        while self.block_len != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bit_length.to_le_bytes());
+
+        let mut digest = [0u8; 16];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+
+        Md5Digest(digest)
+    }
+
+    fn process_block(&mut self) {
+        let mut words = [0u32; 16];
+        for (word, bytes) in words.iter_mut().zip(self.block.chunks_exact(4)) {
+            *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+
+            let f = f
+                .wrapping_add(a)
+                .wrapping_add(MD5_CONSTANTS[i])
+                .wrapping_add(words[g]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(MD5_SHIFTS[i / 16][i % 4]));
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
+
+/// Lookup table for the CRC-32 (IEEE 802.3) polynomial.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Incremental CRC-32 (IEEE 802.3) hasher.
+pub struct Crc32(u32);
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc32 {
+    /// Creates a new hasher.
+    pub fn new() -> Self {
+        Self(!0)
+    }
+
+    /// Adds `data` to the hashed content.
+    pub fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.0 = CRC32_TABLE[((self.0 ^ b as u32) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    /// Returns the checksum of the hashed content.
+    pub fn finalize(self) -> u32 {
+        !self.0
+    }
+}
+
+/// Returns the MD5 digest of the `visible` area of `image`.
+pub fn md5<S: ImageData + ?Sized>(
+    image: &S,
+    visible: (u32, u32),
+) -> Result<Md5Digest, ConvertError> {
+    let mut hasher = Md5::new();
+    for_each_row(image, visible, |row| {
+        hasher.update(row);
+        Ok::<_, ConvertError>(())
+    })?;
+
+    Ok(hasher.finalize())
+}
+
+/// Returns the CRC-32 of the `visible` area of `image`.
+pub fn crc32<S: ImageData + ?Sized>(image: &S, visible: (u32, u32)) -> Result<u32, ConvertError> {
+    let mut hasher = Crc32::new();
+    for_each_row(image, visible, |row| {
+        hasher.update(row);
+        Ok::<_, ConvertError>(())
+    })?;
+
+    Ok(hasher.finalize())
+}
+
+/// Writes the `visible` area of `image` to `writer` as raw planar YUV, i.e. the data hashed by
+/// [`md5`] and [`crc32`].
+pub fn write_raw<W: Write, S: ImageData + ?Sized>(
+    writer: &mut W,
+    image: &S,
+    visible: (u32, u32),
+) -> Result<(), DumpError> {
+    for_each_row(image, visible, |row| {
+        writer.write_all(row)?;
+        Ok::<_, DumpError>(())
+    })
+}
+
+/// Returns the Y4M colorspace tag for `fourcc`.
+fn y4m_colorspace(fourcc: u32) -> Result<&'static str, DumpError> {
+    match fourcc {
+        bindings::VA_FOURCC_NV12 | bindings::VA_FOURCC_I420 | bindings::VA_FOURCC_YV12 => {
+            Ok("420jpeg")
+        }
+        bindings::VA_FOURCC_P010 => Ok("420p10"),
+        bindings::VA_FOURCC_YUY2 => Ok("422"),
+        _ => Err(DumpError::UnsupportedY4mFormat(fourcc)),
+    }
+}
+
+/// Writes frames into a Y4M stream.
+///
+/// The stream header is written along with the first frame, using its format and visible size.
+/// All subsequent frames must have the same size and chroma subsampling.
+pub struct Y4mWriter<W: Write> {
+    writer: W,
+    framerate: (u32, u32),
+    /// Colorspace tag and size of the stream, once the header has been written.
+    stream: Option<(&'static str, (u32, u32))>,
+}
+
+impl<W: Write> Y4mWriter<W> {
+    /// Creates a new Y4M stream writing into `writer`, with a frame rate of
+    /// `framerate.0 / framerate.1` frames per second.
+    pub fn new(writer: W, framerate: (u32, u32)) -> Self {
+        Self {
+            writer,
+            framerate,
+            stream: None,
+        }
+    }
+
+    /// Appends the `visible` area of `image` to the stream.
+    pub fn write_frame<S: ImageData + ?Sized>(
+        &mut self,
+        image: &S,
+        visible: (u32, u32),
+    ) -> Result<(), DumpError> {
+        let va_image = image.va_image();
+        let colorspace = y4m_colorspace(va_image.format.fourcc)?;
+        let size = (
+            std::cmp::min(visible.0, u32::from(va_image.width)),
+            std::cmp::min(visible.1, u32::from(va_image.height)),
+        );
+
+        match self.stream {
+            None => {
+                writeln!(
+                    self.writer,
+                    "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C{}",
+                    size.0, size.1, self.framerate.0, self.framerate.1, colorspace
+                )?;
+                self.stream = Some((colorspace, size));
+            }
+            Some(stream) if stream != (colorspace, size) => return Err(DumpError::FrameMismatch),
+            Some(_) => (),
+        }
+
+        writeln!(self.writer, "FRAME")?;
+        write_raw(&mut self.writer, image, size)
+    }
+
+    /// Consumes the stream and returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::convert;
+    use crate::convert::RawImage;
+    use crate::ImageDataMut;
+
+    #[test]
+    fn md5_vectors() {
+        let digest = |data: &[u8]| {
+            let mut hasher = Md5::new();
+            hasher.update(data);
+            hasher.finalize().to_string()
+        };
+
+        assert_eq!(digest(b""), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(digest(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(
+            digest(&b"1234567890".repeat(8)),
+            "57edf4a22be3c955ac49da2e2107b67a"
+        );
+    }
+
+    #[test]
+    fn crc32_vector() {
+        let mut hasher = Crc32::new();
+        hasher.update(b"123456789");
+        assert_eq!(hasher.finalize(), 0xcbf43926);
+    }
+
+    #[test]
+    fn same_checksum_across_formats() {
+        let mut nv12 = RawImage::new(bindings::VA_FOURCC_NV12, 6, 4).unwrap();
+        for (i, b) in nv12.data_mut().iter_mut().enumerate() {
+            *b = i as u8;
+        }
+
+        for fourcc in [bindings::VA_FOURCC_I420, bindings::VA_FOURCC_YV12] {
+            let mut other = RawImage::new(fourcc, 6, 4).unwrap();
+            convert(&nv12, &mut other, Default::default()).unwrap();
+            assert_eq!(md5(&nv12, (5, 3)).unwrap(), md5(&other, (5, 3)).unwrap());
+            assert_eq!(
+                crc32(&nv12, (5, 3)).unwrap(),
+                crc32(&other, (5, 3)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn p010_is_dumped_as_16bit_samples() {
+        let mut p010 = RawImage::new(bindings::VA_FOURCC_P010, 2, 2).unwrap();
+        // Luma samples 0x3ff, 1, 2, 3, then chroma samples 0x200 and 0x100.
+        p010.data_mut().copy_from_slice(&[
+            0xc0, 0xff, 0x40, 0x00, 0x80, 0x00, 0xc0, 0x00, 0x00, 0x80, 0x00, 0x40,
+        ]);
+
+        let mut raw = Vec::new();
+        write_raw(&mut raw, &p010, (2, 2)).unwrap();
+        assert_eq!(raw, [0xff, 0x03, 1, 0, 2, 0, 3, 0, 0x00, 0x02, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn y4m_stream() {
+        let nv12 = RawImage::new(bindings::VA_FOURCC_NV12, 4, 4).unwrap();
+        let mut writer = Y4mWriter::new(Vec::new(), (30, 1));
+        writer.write_frame(&nv12, (4, 2)).unwrap();
+        writer.write_frame(&nv12, (4, 2)).unwrap();
+        assert!(matches!(
+            writer.write_frame(&nv12, (4, 4)),
+            Err(DumpError::FrameMismatch)
+        ));
+
+        let header = b"YUV4MPEG2 W4 H2 F30:1 Ip A1:1 C420jpeg\n";
+        let frame_size = b"FRAME\n".len() + 4 * 2 + 2 * 2;
+        let stream = writer.into_inner();
+        assert!(stream.starts_with(header));
+        assert_eq!(stream.len(), header.len() + frame_size * 2);
+    }
+}
@@ -28,6 +28,62 @@ pub enum QuerySurfaceAttributesError {
     GenericValueError(#[from] GenericValueError),
 }
 
+/// Parameters of a [`Config::query_processing_rate`] call.
+///
+/// Note that libva does not take the resolution into account: the returned rate is the one
+/// achievable at the maximum resolution supported by the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessingRateParameter {
+    /// Parameters for a decoding config.
+    Decode {
+        /// Level of the stream, using the codec's `level_idc` syntax.
+        level_idc: u8,
+    },
+    /// Parameters for an encoding config.
+    Encode {
+        /// Level of the stream, using the codec's `level_idc` syntax.
+        level_idc: u8,
+        /// Quality level, as set with [`crate::EncMiscParameterBufferQualityLevel`].
+        quality_level: u32,
+        /// Period between two intra frames.
+        intra_period: u32,
+        /// Period between two I or P frames.
+        ip_period: u32,
+    },
+}
+
+impl ProcessingRateParameter {
+    /// Returns the FFI `VAProcessingRateParameter` corresponding to these parameters.
+    fn to_va(self) -> bindings::VAProcessingRateParameter {
+        let __bindgen_anon_1 = match self {
+            ProcessingRateParameter::Decode { level_idc } => {
+                bindings::_VAProcessingRateParameter__bindgen_ty_1 {
+                    proc_buf_dec: bindings::VAProcessingRateParameterDec {
+                        level_idc,
+                        ..Default::default()
+                    },
+                }
+            }
+            ProcessingRateParameter::Encode {
+                level_idc,
+                quality_level,
+                intra_period,
+                ip_period,
+            } => bindings::_VAProcessingRateParameter__bindgen_ty_1 {
+                proc_buf_enc: bindings::VAProcessingRateParameterEnc {
+                    level_idc,
+                    quality_level,
+                    intra_period,
+                    ip_period,
+                    ..Default::default()
+                },
+            },
+        };
+
+        bindings::VAProcessingRateParameter { __bindgen_anon_1 }
+    }
+}
+
 impl Config {
     /// Creates a Config by wrapping around the `vaCreateConfig` call. This is just a helper for
     /// [`Display::create_config`].
@@ -65,6 +121,31 @@ impl Config {
         self.id
     }
 
+    /// Returns the number of frames per second this config can process with `parameters`, by
+    /// wrapping around `vaQueryProcessingRate`.
+    ///
+    /// Support for this query is advertised by the `VAConfigAttribProcessingRate` attribute.
+    pub fn query_processing_rate(
+        &self,
+        parameters: ProcessingRateParameter,
+    ) -> Result<u32, VaError> {
+        let mut proc_buf = parameters.to_va();
+        let mut processing_rate = 0;
+
+        // Safe because `self` represents a valid VAConfig and `proc_buf` is a properly
+        // initialized `VAProcessingRateParameter`.
+        va_check(unsafe {
+            bindings::vaQueryProcessingRate(
+                self.display.handle(),
+                self.id,
+                &mut proc_buf,
+                &mut processing_rate,
+            )
+        })?;
+
+        Ok(processing_rate)
+    }
+
     // Queries surface attributes for this config.
     //
     // This function queries for all supported attributes for this configuration. In particular, if
@@ -135,3 +216,47 @@ impl Drop for Config {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the bytes of `value`, which must not contain padding.
+    fn as_bytes<T>(value: &T) -> &[u8] {
+        // Safe because `value` is valid for reading `size_of_val(value)` bytes, all of which are
+        // initialized as `T` has no padding.
+        unsafe {
+            std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of_val(value))
+        }
+    }
+
+    #[test]
+    fn processing_rate_parameters() {
+        let dec = ProcessingRateParameter::Decode { level_idc: 41 }.to_va();
+        // Safe because `to_va` initializes `proc_buf_dec` for decoding parameters.
+        let dec = unsafe { dec.__bindgen_anon_1.proc_buf_dec };
+        assert_eq!(dec.level_idc, 41);
+        assert!(as_bytes(&dec)[1..].iter().all(|b| *b == 0));
+
+        let enc = ProcessingRateParameter::Encode {
+            level_idc: 51,
+            quality_level: 4,
+            intra_period: 30,
+            ip_period: 3,
+        }
+        .to_va();
+        // Safe because `to_va` initializes `proc_buf_enc` for encoding parameters.
+        let enc = unsafe { enc.__bindgen_anon_1.proc_buf_enc };
+        assert_eq!(enc.level_idc, 51);
+        assert_eq!(enc.quality_level, 4);
+        assert_eq!(enc.intra_period, 30);
+        assert_eq!(enc.ip_period, 3);
+
+        // `level_idc` is followed by 3 reserved bytes, then by the 32-bit members.
+        let bytes = as_bytes(&enc);
+        assert_eq!(bytes[..4], [51, 0, 0, 0]);
+        assert_eq!(bytes[4..8], 4u32.to_ne_bytes());
+        assert_eq!(bytes[8..12], 30u32.to_ne_bytes());
+        assert_eq!(bytes[12..16], 3u32.to_ne_bytes());
+    }
+}
@@ -8,11 +8,13 @@ use log::error;
 
 use crate::bindings;
 use crate::buffer::Buffer;
+use crate::buffer::Buffer2D;
 use crate::buffer::BufferType;
 use crate::display::Display;
 use crate::va_check;
 use crate::Config;
 use crate::EncCodedBuffer;
+use crate::EncQPMap;
 use crate::Surface;
 use crate::SurfaceMemoryDescriptor;
 use crate::VaError;
@@ -79,10 +81,52 @@ impl Context {
     }
 
     /// Create a new buffer of type `type_`.
+    ///
+    /// Fails with `VA_STATUS_ERROR_INVALID_PARAMETER` if `type_` is an array without any element,
+    /// e.g. a slice parameter buffer to which no slice has been added.
     pub fn create_buffer(self: &Rc<Self>, type_: BufferType) -> Result<Buffer, VaError> {
         Buffer::new(Rc::clone(self), type_)
     }
 
+    /// Creates a new slice data buffer containing a copy of `data`.
+    ///
+    /// Contrary to [`BufferType::SliceData`], `data` does not need to be moved into a `Vec` first
+    /// and can be borrowed directly from the bitstream.
+    pub fn create_slice_data(self: &Rc<Self>, data: &[u8]) -> Result<Buffer, VaError> {
+        Buffer::new_slice_data(Rc::clone(self), data)
+    }
+
+    /// Creates a new slice data buffer of `size` bytes without initializing it.
+    ///
+    /// The content of the buffer is unspecified and is meant to be written in place using
+    /// [`Buffer::map_mut`], avoiding any intermediate copy of the slice data.
+    pub fn create_slice_data_uninit(self: &Rc<Self>, size: usize) -> Result<Buffer, VaError> {
+        Buffer::new_slice_data_uninit(Rc::clone(self), size)
+    }
+
+    /// Creates a new surface-shaped buffer of type `type_` and `width`x`height` units by wrapping
+    /// a `vaCreateBuffer2` call. The unit size and pitch are chosen by the driver.
+    pub fn create_buffer_2d(
+        self: &Rc<Self>,
+        type_: bindings::VABufferType::Type,
+        width: u32,
+        height: u32,
+    ) -> Result<Buffer2D, VaError> {
+        Buffer2D::new(Rc::clone(self), type_, width, height)
+    }
+
+    /// Creates a new per-block QP map for a coded picture of `coded_width`x`coded_height`
+    /// pixels, using blocks of `block_size` pixels as returned by
+    /// [`Display::query_qp_block_size`].
+    pub fn create_enc_qp_map(
+        self: &Rc<Self>,
+        block_size: u32,
+        coded_width: u32,
+        coded_height: u32,
+    ) -> Result<EncQPMap, VaError> {
+        EncQPMap::new(Rc::clone(self), block_size, coded_width, coded_height)
+    }
+
     /// Create a new buffer of type `type_`.
     pub fn create_enc_coded(self: &Rc<Self>, size: usize) -> Result<EncCodedBuffer, VaError> {
         EncCodedBuffer::new(Rc::clone(self), size)
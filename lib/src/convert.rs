@@ -0,0 +1,719 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+//! Software conversion between common pixel formats.
+//!
+//! Many drivers can only derive or create images in the native format of a surface, which makes
+//! [`Image::create_from`] fail when another format is requested. The functions of this module
+//! perform the conversion on the CPU instead, working directly on the planes of anything
+//! implementing [`ImageData`].
+//!
+//! The supported conversions are NV12 to and from I420, YV12, P010 and YUY2, and NV12, I420, YV12,
+//! P010 and YUY2 to RGBA.
+
+use thiserror::Error;
+
+use crate::bindings;
+use crate::Image;
+use crate::ImageData;
+use crate::ImageDataMut;
+use crate::Surface;
+use crate::SurfaceMemoryDescriptor;
+use crate::VaError;
+
+/// Error type for the conversion functions.
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    #[error("unsupported conversion from fourcc {0:#010x} to fourcc {1:#010x}")]
+    UnsupportedConversion(u32, u32),
+    #[error("unsupported fourcc {0:#010x}")]
+    UnsupportedFormat(u32),
+    #[error("plane {0} does not fit in the image data")]
+    PlaneOutOfBounds(usize),
+}
+
+/// Error type for [`download`] and [`upload`].
+#[derive(Debug, Error)]
+pub enum TransferError {
+    #[error("error while accessing the surface: {0}")]
+    VaError(#[from] VaError),
+    #[error("error while converting the image: {0}")]
+    ConvertError(#[from] ConvertError),
+}
+
+/// Matrix used to convert between YUV and RGB.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMatrix {
+    /// ITU-R BT.601.
+    #[default]
+    Bt601,
+    /// ITU-R BT.709.
+    Bt709,
+}
+
+impl ColorMatrix {
+    /// Returns the `(Kr, Kb)` coefficients of the matrix.
+    fn coefficients(&self) -> (f32, f32) {
+        match self {
+            ColorMatrix::Bt601 => (0.299, 0.114),
+            ColorMatrix::Bt709 => (0.2126, 0.0722),
+        }
+    }
+}
+
+/// Range of the YUV samples.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorRange {
+    /// Luma in `[16, 235]` and chroma in `[16, 240]`.
+    #[default]
+    Limited,
+    /// Luma and chroma use the whole `[0, 255]` range.
+    Full,
+}
+
+/// Describes how YUV samples are to be interpreted when converting to RGB.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Colorimetry {
+    pub matrix: ColorMatrix,
+    pub range: ColorRange,
+}
+
+/// Returns the `(row length in bytes, number of rows)` of each plane of an image of format
+/// `fourcc` and size `width`x`height`, or `None` if `fourcc` is not supported.
+pub(crate) fn plane_sizes(fourcc: u32, width: usize, height: usize) -> Option<Vec<(usize, usize)>> {
+    let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+
+    let sizes = match fourcc {
+        bindings::VA_FOURCC_NV12 => vec![(width, height), (cw * 2, ch)],
+        bindings::VA_FOURCC_I420 | bindings::VA_FOURCC_YV12 => {
+            vec![(width, height), (cw, ch), (cw, ch)]
+        }
+        bindings::VA_FOURCC_P010 => vec![(width * 2, height), (cw * 4, ch)],
+        bindings::VA_FOURCC_YUY2 => vec![(cw * 4, height)],
+        bindings::VA_FOURCC_RGBA => vec![(width * 4, height)],
+        _ => return None,
+    };
+
+    Some(sizes)
+}
+
+/// Returns the `VAImageFormat` describing `fourcc`, or `None` if `fourcc` is not supported.
+fn image_format(fourcc: u32) -> Option<bindings::VAImageFormat> {
+    let mut format = bindings::VAImageFormat {
+        fourcc,
+        byte_order: bindings::VA_LSB_FIRST,
+        ..Default::default()
+    };
+
+    match fourcc {
+        bindings::VA_FOURCC_NV12 | bindings::VA_FOURCC_I420 | bindings::VA_FOURCC_YV12 => {
+            format.bits_per_pixel = 12
+        }
+        bindings::VA_FOURCC_P010 => format.bits_per_pixel = 24,
+        bindings::VA_FOURCC_YUY2 => format.bits_per_pixel = 16,
+        bindings::VA_FOURCC_RGBA => {
+            format.bits_per_pixel = 32;
+            format.depth = 32;
+            format.red_mask = 0x000000ff;
+            format.green_mask = 0x0000ff00;
+            format.blue_mask = 0x00ff0000;
+            format.alpha_mask = 0xff000000;
+        }
+        _ => return None,
+    }
+
+    Some(format)
+}
+
+/// An image whose pixel data is stored in memory, tightly packed.
+///
+/// This is useful as the destination of a [`download`], or to prepare data for an [`upload`].
+pub struct RawImage {
+    image: bindings::VAImage,
+    data: Vec<u8>,
+}
+
+impl RawImage {
+    /// Creates a zero-filled image of format `fourcc` and size `width`x`height`.
+    pub fn new(fourcc: u32, width: u16, height: u16) -> Result<Self, ConvertError> {
+        let format = image_format(fourcc).ok_or(ConvertError::UnsupportedFormat(fourcc))?;
+        let sizes = plane_sizes(fourcc, width as usize, height as usize)
+            .ok_or(ConvertError::UnsupportedFormat(fourcc))?;
+
+        let mut image = bindings::VAImage {
+            image_id: bindings::VA_INVALID_ID,
+            buf: bindings::VA_INVALID_ID,
+            format,
+            width,
+            height,
+            num_planes: sizes.len() as u32,
+            ..Default::default()
+        };
+
+        let mut offset = 0;
+        for (i, (row_len, rows)) in sizes.into_iter().enumerate() {
+            image.pitches[i] = row_len as u32;
+            image.offsets[i] = offset as u32;
+            offset += row_len * rows;
+        }
+        image.data_size = offset as u32;
+
+        Ok(Self {
+            image,
+            data: vec![0; offset],
+        })
+    }
+
+    /// Returns the `VAImage` describing the layout of this image.
+    pub fn image(&self) -> &bindings::VAImage {
+        &self.image
+    }
+
+    /// Consumes the image and returns its pixel data.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl ImageData for RawImage {
+    fn va_image(&self) -> &bindings::VAImage {
+        &self.image
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl ImageDataMut for RawImage {
+    fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+}
+
+/// Checks that `rows` rows of `row_len` bytes of plane `plane` of `image` fit into a buffer of
+/// `data_len` bytes, and returns the offset and pitch of the plane.
+fn plane_layout(
+    image: &bindings::VAImage,
+    data_len: usize,
+    plane: usize,
+    row_len: usize,
+    rows: usize,
+) -> Result<(usize, usize), ConvertError> {
+    if plane >= image.num_planes as usize || plane >= image.pitches.len() {
+        return Err(ConvertError::PlaneOutOfBounds(plane));
+    }
+
+    let offset = image.offsets[plane] as usize;
+    let pitch = image.pitches[plane] as usize;
+    let end = match rows {
+        0 => offset,
+        rows => offset + pitch * (rows - 1) + row_len,
+    };
+
+    if pitch < row_len || end > data_len {
+        return Err(ConvertError::PlaneOutOfBounds(plane));
+    }
+
+    Ok((offset, pitch))
+}
+
+/// Returns the first `row_len` bytes of each of the first `rows` rows of plane `plane`.
+pub(crate) fn plane_rows<'a>(
+    image: &bindings::VAImage,
+    data: &'a [u8],
+    plane: usize,
+    row_len: usize,
+    rows: usize,
+) -> Result<Vec<&'a [u8]>, ConvertError> {
+    let (offset, pitch) = plane_layout(image, data.len(), plane, row_len, rows)?;
+
+    Ok((0..rows)
+        .map(|y| &data[offset + y * pitch..offset + y * pitch + row_len])
+        .collect())
+}
+
+/// Mutable version of [`plane_rows`], returning the rows of all the planes at once.
+///
+/// `sizes` contains the row length and number of rows to return for each plane.
+fn planes_rows_mut<'a>(
+    image: &bindings::VAImage,
+    data: &'a mut [u8],
+    sizes: &[(usize, usize)],
+) -> Result<Vec<Vec<&'a mut [u8]>>, ConvertError> {
+    let mut layouts = Vec::with_capacity(sizes.len());
+    for (plane, &(row_len, rows)) in sizes.iter().enumerate() {
+        let (offset, pitch) = plane_layout(image, data.len(), plane, row_len, rows)?;
+        layouts.push((plane, offset, pitch));
+    }
+
+    // Split the data into rows in increasing offset order, so planes can be handed out
+    // independently. Overlapping planes are rejected.
+    layouts.sort_by_key(|&(_, offset, _)| offset);
+    let mut planes: Vec<Vec<&'a mut [u8]>> = sizes.iter().map(|_| Vec::new()).collect();
+    let mut rest = data;
+    let mut consumed = 0;
+    for (plane, offset, pitch) in layouts {
+        let (row_len, rows) = sizes[plane];
+        if offset < consumed {
+            return Err(ConvertError::PlaneOutOfBounds(plane));
+        }
+
+        let (_, tail) = std::mem::take(&mut rest).split_at_mut(offset - consumed);
+        rest = tail;
+        consumed = offset;
+
+        for y in 0..rows {
+            let skip = if y + 1 < rows { pitch } else { row_len };
+            let (row, tail) = std::mem::take(&mut rest).split_at_mut(skip);
+            planes[plane].push(&mut row[..row_len]);
+            rest = tail;
+            consumed += skip;
+        }
+    }
+
+    Ok(planes)
+}
+
+/// Reads a 16-bit little-endian sample from `bytes` at sample index `i`.
+fn sample16(bytes: &[u8], i: usize) -> u16 {
+    u16::from_le_bytes([bytes[i * 2], bytes[i * 2 + 1]])
+}
+
+/// 8-bit 4:2:0 planar frame used as intermediate representation for conversions.
+struct Frame420 {
+    width: usize,
+    height: usize,
+    y: Vec<u8>,
+    u: Vec<u8>,
+    v: Vec<u8>,
+}
+
+impl Frame420 {
+    fn chroma_width(&self) -> usize {
+        self.width.div_ceil(2)
+    }
+
+    /// Reads the `width`x`height` top-left area of `src` into a new frame.
+    fn read<S: ImageData + ?Sized>(
+        src: &S,
+        width: usize,
+        height: usize,
+    ) -> Result<Self, ConvertError> {
+        let image = src.va_image();
+        let data = src.data();
+        let fourcc = image.format.fourcc;
+        let sizes =
+            plane_sizes(fourcc, width, height).ok_or(ConvertError::UnsupportedFormat(fourcc))?;
+        let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+
+        let mut frame = Frame420 {
+            width,
+            height,
+            y: Vec::with_capacity(width * height),
+            u: Vec::with_capacity(cw * ch),
+            v: Vec::with_capacity(cw * ch),
+        };
+
+        match fourcc {
+            bindings::VA_FOURCC_NV12 => {
+                for row in plane_rows(image, data, 0, sizes[0].0, sizes[0].1)? {
+                    frame.y.extend_from_slice(row);
+                }
+                for row in plane_rows(image, data, 1, sizes[1].0, sizes[1].1)? {
+                    for uv in row.chunks_exact(2) {
+                        frame.u.push(uv[0]);
+                        frame.v.push(uv[1]);
+                    }
+                }
+            }
+            bindings::VA_FOURCC_I420 | bindings::VA_FOURCC_YV12 => {
+                let (u_plane, v_plane) = match fourcc {
+                    bindings::VA_FOURCC_I420 => (1, 2),
+                    _ => (2, 1),
+                };
+
+                for row in plane_rows(image, data, 0, sizes[0].0, sizes[0].1)? {
+                    frame.y.extend_from_slice(row);
+                }
+                for row in plane_rows(image, data, u_plane, cw, ch)? {
+                    frame.u.extend_from_slice(row);
+                }
+                for row in plane_rows(image, data, v_plane, cw, ch)? {
+                    frame.v.extend_from_slice(row);
+                }
+            }
+            bindings::VA_FOURCC_P010 => {
+                for row in plane_rows(image, data, 0, sizes[0].0, sizes[0].1)? {
+                    frame
+                        .y
+                        .extend((0..width).map(|x| (sample16(row, x) >> 8) as u8));
+                }
+                for row in plane_rows(image, data, 1, sizes[1].0, sizes[1].1)? {
+                    for x in 0..cw {
+                        frame.u.push((sample16(row, x * 2) >> 8) as u8);
+                        frame.v.push((sample16(row, x * 2 + 1) >> 8) as u8);
+                    }
+                }
+            }
+            bindings::VA_FOURCC_YUY2 => {
+                let rows = plane_rows(image, data, 0, sizes[0].0, sizes[0].1)?;
+                for row in &rows {
+                    frame
+                        .y
+                        .extend(row.chunks_exact(2).map(|yc| yc[0]).take(width));
+                }
+                // Vertically average the 4:2:2 chroma into 4:2:0.
+                for pair in rows.chunks(2) {
+                    let (top, bottom) = (pair[0], pair[pair.len() - 1]);
+                    for x in 0..cw {
+                        let avg = |i: usize| (top[i] as u16 + bottom[i] as u16).div_ceil(2) as u8;
+                        frame.u.push(avg(x * 4 + 1));
+                        frame.v.push(avg(x * 4 + 3));
+                    }
+                }
+            }
+            _ => return Err(ConvertError::UnsupportedFormat(fourcc)),
+        }
+
+        Ok(frame)
+    }
+
+    /// Writes the frame into the top-left area of `dst`.
+    fn write<D: ImageDataMut + ?Sized>(
+        &self,
+        dst: &mut D,
+        colorimetry: Colorimetry,
+    ) -> Result<(), ConvertError> {
+        let fourcc = dst.va_image().format.fourcc;
+        let image = *dst.va_image();
+        let sizes = plane_sizes(fourcc, self.width, self.height)
+            .ok_or(ConvertError::UnsupportedFormat(fourcc))?;
+        let mut planes = planes_rows_mut(&image, dst.data_mut(), &sizes)?;
+        let (w, cw) = (self.width, self.chroma_width());
+
+        match fourcc {
+            bindings::VA_FOURCC_NV12 => {
+                for (y, row) in planes[0].iter_mut().enumerate() {
+                    row.copy_from_slice(&self.y[y * w..(y + 1) * w]);
+                }
+                for (y, row) in planes[1].iter_mut().enumerate() {
+                    for (x, uv) in row.chunks_exact_mut(2).enumerate() {
+                        uv[0] = self.u[y * cw + x];
+                        uv[1] = self.v[y * cw + x];
+                    }
+                }
+            }
+            bindings::VA_FOURCC_I420 | bindings::VA_FOURCC_YV12 => {
+                let (u_plane, v_plane) = match fourcc {
+                    bindings::VA_FOURCC_I420 => (1, 2),
+                    _ => (2, 1),
+                };
+
+                for (y, row) in planes[0].iter_mut().enumerate() {
+                    row.copy_from_slice(&self.y[y * w..(y + 1) * w]);
+                }
+                for (y, row) in planes[u_plane].iter_mut().enumerate() {
+                    row.copy_from_slice(&self.u[y * cw..(y + 1) * cw]);
+                }
+                for (y, row) in planes[v_plane].iter_mut().enumerate() {
+                    row.copy_from_slice(&self.v[y * cw..(y + 1) * cw]);
+                }
+            }
+            bindings::VA_FOURCC_P010 => {
+                for (y, row) in planes[0].iter_mut().enumerate() {
+                    for (x, sample) in row.chunks_exact_mut(2).enumerate() {
+                        sample.copy_from_slice(&((self.y[y * w + x] as u16) << 8).to_le_bytes());
+                    }
+                }
+                for (y, row) in planes[1].iter_mut().enumerate() {
+                    for (x, uv) in row.chunks_exact_mut(4).enumerate() {
+                        uv[0..2].copy_from_slice(&((self.u[y * cw + x] as u16) << 8).to_le_bytes());
+                        uv[2..4].copy_from_slice(&((self.v[y * cw + x] as u16) << 8).to_le_bytes());
+                    }
+                }
+            }
+            bindings::VA_FOURCC_YUY2 => {
+                for (y, row) in planes[0].iter_mut().enumerate() {
+                    let c = (y / 2) * cw;
+                    for (x, yuyv) in row.chunks_exact_mut(4).enumerate() {
+                        // Duplicate the last luma sample if the width is odd.
+                        let y1 = (x * 2 + 1).min(w - 1);
+                        yuyv[0] = self.y[y * w + x * 2];
+                        yuyv[1] = self.u[c + x];
+                        yuyv[2] = self.y[y * w + y1];
+                        yuyv[3] = self.v[c + x];
+                    }
+                }
+            }
+            bindings::VA_FOURCC_RGBA => {
+                for (y, row) in planes[0].iter_mut().enumerate() {
+                    let c = (y / 2) * cw;
+                    for (x, rgba) in row.chunks_exact_mut(4).enumerate() {
+                        let (r, g, b) = yuv_to_rgb(
+                            self.y[y * w + x],
+                            self.u[c + x / 2],
+                            self.v[c + x / 2],
+                            colorimetry,
+                        );
+                        rgba.copy_from_slice(&[r, g, b, 0xff]);
+                    }
+                }
+            }
+            _ => return Err(ConvertError::UnsupportedFormat(fourcc)),
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a single YUV sample to RGB.
+fn yuv_to_rgb(y: u8, u: u8, v: u8, colorimetry: Colorimetry) -> (u8, u8, u8) {
+    let (kr, kb) = colorimetry.matrix.coefficients();
+    let kg = 1.0 - kr - kb;
+
+    let (y, u, v) = match colorimetry.range {
+        ColorRange::Limited => (
+            (y as f32 - 16.0) * 255.0 / 219.0,
+            (u as f32 - 128.0) * 255.0 / 224.0,
+            (v as f32 - 128.0) * 255.0 / 224.0,
+        ),
+        ColorRange::Full => (y as f32, u as f32 - 128.0, v as f32 - 128.0),
+    };
+
+    let r = y + 2.0 * (1.0 - kr) * v;
+    let g = y - (2.0 * kb * (1.0 - kb) / kg) * u - (2.0 * kr * (1.0 - kr) / kg) * v;
+    let b = y + 2.0 * (1.0 - kb) * u;
+
+    let clamp = |c: f32| c.round().clamp(0.0, 255.0) as u8;
+
+    (clamp(r), clamp(g), clamp(b))
+}
+
+/// Copies the planes of `src` into `dst`, which must have the same format.
+fn copy_planes<S: ImageData + ?Sized, D: ImageDataMut + ?Sized>(
+    src: &S,
+    dst: &mut D,
+    width: usize,
+    height: usize,
+) -> Result<(), ConvertError> {
+    let fourcc = src.va_image().format.fourcc;
+    let sizes =
+        plane_sizes(fourcc, width, height).ok_or(ConvertError::UnsupportedFormat(fourcc))?;
+    let image = *dst.va_image();
+    let dst_planes = planes_rows_mut(&image, dst.data_mut(), &sizes)?;
+
+    for (plane, dst_rows) in dst_planes.into_iter().enumerate() {
+        let (row_len, rows) = sizes[plane];
+        let src_rows = plane_rows(src.va_image(), src.data(), plane, row_len, rows)?;
+        for (dst_row, src_row) in dst_rows.into_iter().zip(src_rows) {
+            dst_row.copy_from_slice(src_row);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns whether [`convert`] can convert images of format `src` into images of format `dst`.
+pub fn is_supported(src: u32, dst: u32) -> bool {
+    const YUV: [u32; 5] = [
+        bindings::VA_FOURCC_NV12,
+        bindings::VA_FOURCC_I420,
+        bindings::VA_FOURCC_YV12,
+        bindings::VA_FOURCC_P010,
+        bindings::VA_FOURCC_YUY2,
+    ];
+
+    let same_format = src == dst && plane_sizes(src, 0, 0).is_some();
+    let yuv_to_yuv = (src == bindings::VA_FOURCC_NV12 || dst == bindings::VA_FOURCC_NV12)
+        && YUV.contains(&src)
+        && YUV.contains(&dst);
+    let yuv_to_rgb = YUV.contains(&src) && dst == bindings::VA_FOURCC_RGBA;
+
+    same_format || yuv_to_yuv || yuv_to_rgb
+}
+
+/// Converts the content of `src` into the format of `dst`.
+///
+/// Only the area common to both images is converted. `colorimetry` is used when converting YUV
+/// data to RGB, and ignored otherwise.
+pub fn convert<S: ImageData + ?Sized, D: ImageDataMut + ?Sized>(
+    src: &S,
+    dst: &mut D,
+    colorimetry: Colorimetry,
+) -> Result<(), ConvertError> {
+    let src_fourcc = src.va_image().format.fourcc;
+    let dst_fourcc = dst.va_image().format.fourcc;
+    if !is_supported(src_fourcc, dst_fourcc) {
+        return Err(ConvertError::UnsupportedConversion(src_fourcc, dst_fourcc));
+    }
+
+    let width = std::cmp::min(src.va_image().width, dst.va_image().width) as usize;
+    let height = std::cmp::min(src.va_image().height, dst.va_image().height) as usize;
+
+    if src_fourcc == dst_fourcc {
+        copy_planes(src, dst, width, height)
+    } else {
+        Frame420::read(src, width, height)?.write(dst, colorimetry)
+    }
+}
+
+/// Returns the formats supported by the display of `surface` that can be converted to or from
+/// (depending on `to_surface`) `fourcc`.
+fn fallback_formats<D: SurfaceMemoryDescriptor>(
+    surface: &Surface<D>,
+    fourcc: u32,
+    to_surface: bool,
+) -> Result<Vec<bindings::VAImageFormat>, VaError> {
+    Ok(surface
+        .display()
+        .query_image_formats()?
+        .into_iter()
+        .filter(|format| format.fourcc != fourcc)
+        .filter(|format| match to_surface {
+            true => is_supported(fourcc, format.fourcc),
+            false => is_supported(format.fourcc, fourcc),
+        })
+        .collect())
+}
+
+/// Reads the content of `surface` into `dst`, converting it to the format of `dst`.
+///
+/// The driver is first asked to perform the conversion. If it cannot, the surface is read in a
+/// format the driver supports, either by deriving it or by trying the other image formats of the
+/// display, and converted in software.
+pub fn download<D: SurfaceMemoryDescriptor, I: ImageDataMut + ?Sized>(
+    surface: &Surface<D>,
+    dst: &mut I,
+    colorimetry: Colorimetry,
+) -> Result<(), TransferError> {
+    let size = surface.size();
+    let format = dst.va_image().format;
+
+    let err = match Image::create_from(surface, format, size, size) {
+        Ok(image) => return Ok(convert(&image, dst, colorimetry)?),
+        Err(e) => e,
+    };
+
+    if let Ok(image) = Image::derive_from(surface, size) {
+        if is_supported(image.image().format.fourcc, format.fourcc) {
+            return Ok(convert(&image, dst, colorimetry)?);
+        }
+    }
+
+    for fallback in fallback_formats(surface, format.fourcc, false)? {
+        if let Ok(image) = Image::create_from(surface, fallback, size, size) {
+            return Ok(convert(&image, dst, colorimetry)?);
+        }
+    }
+
+    Err(err.into())
+}
+
+/// Writes the content of `src` into `surface`, converting it to a format supported by the driver.
+///
+/// The driver is first asked to accept the format of `src`. If it cannot, the surface is either
+/// derived or mapped with another image format of the display, and `src` is converted into it in
+/// software.
+pub fn upload<D: SurfaceMemoryDescriptor, I: ImageData + ?Sized>(
+    surface: &Surface<D>,
+    src: &I,
+    colorimetry: Colorimetry,
+) -> Result<(), TransferError> {
+    let size = surface.size();
+    let format = src.va_image().format;
+
+    // The images write their content back to the surface when dropped.
+    let err = match Image::create_from(surface, format, size, size) {
+        Ok(mut image) => return Ok(convert(src, &mut image, colorimetry)?),
+        Err(e) => e,
+    };
+
+    if let Ok(mut image) = Image::derive_from(surface, size) {
+        if is_supported(format.fourcc, image.image().format.fourcc) {
+            return Ok(convert(src, &mut image, colorimetry)?);
+        }
+    }
+
+    for fallback in fallback_formats(surface, format.fourcc, true)? {
+        if let Ok(mut image) = Image::create_from(surface, fallback, size, size) {
+            return Ok(convert(src, &mut image, colorimetry)?);
+        }
+    }
+
+    Err(err.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a `width`x`height` NV12 image with distinct values for every sample.
+    fn nv12_pattern(width: u16, height: u16) -> RawImage {
+        let mut image = RawImage::new(bindings::VA_FOURCC_NV12, width, height).unwrap();
+        for (i, b) in image.data_mut().iter_mut().enumerate() {
+            *b = (i * 7 % 251) as u8;
+        }
+        image
+    }
+
+    #[test]
+    fn nv12_roundtrip() {
+        let nv12 = nv12_pattern(18, 9);
+
+        for fourcc in [
+            bindings::VA_FOURCC_I420,
+            bindings::VA_FOURCC_YV12,
+            bindings::VA_FOURCC_P010,
+        ] {
+            let mut converted = RawImage::new(fourcc, 18, 9).unwrap();
+            convert(&nv12, &mut converted, Default::default()).unwrap();
+            let mut back = RawImage::new(bindings::VA_FOURCC_NV12, 18, 9).unwrap();
+            convert(&converted, &mut back, Default::default()).unwrap();
+            assert_eq!(nv12.data(), back.data(), "fourcc {:#x}", fourcc);
+        }
+    }
+
+    #[test]
+    fn yuy2_to_nv12() {
+        let mut yuy2 = RawImage::new(bindings::VA_FOURCC_YUY2, 2, 2).unwrap();
+        yuy2.data_mut()
+            .copy_from_slice(&[10, 100, 20, 200, 30, 110, 40, 210]);
+        let mut nv12 = RawImage::new(bindings::VA_FOURCC_NV12, 2, 2).unwrap();
+        convert(&yuy2, &mut nv12, Default::default()).unwrap();
+
+        assert_eq!(nv12.data(), &[10, 20, 30, 40, 105, 205]);
+    }
+
+    #[test]
+    fn nv12_to_rgba() {
+        let mut nv12 = RawImage::new(bindings::VA_FOURCC_NV12, 2, 2).unwrap();
+        nv12.data_mut()
+            .copy_from_slice(&[235, 235, 16, 16, 128, 128]);
+
+        for matrix in [ColorMatrix::Bt601, ColorMatrix::Bt709] {
+            let mut rgba = RawImage::new(bindings::VA_FOURCC_RGBA, 2, 2).unwrap();
+            let colorimetry = Colorimetry {
+                matrix,
+                range: ColorRange::Limited,
+            };
+            convert(&nv12, &mut rgba, colorimetry).unwrap();
+            assert_eq!(
+                rgba.data(),
+                &[255, 255, 255, 255, 255, 255, 255, 255, 0, 0, 0, 255, 0, 0, 0, 255]
+            );
+        }
+    }
+
+    #[test]
+    fn unsupported_conversion() {
+        let rgba = RawImage::new(bindings::VA_FOURCC_RGBA, 2, 2).unwrap();
+        let mut nv12 = RawImage::new(bindings::VA_FOURCC_NV12, 2, 2).unwrap();
+
+        assert!(matches!(
+            convert(&rgba, &mut nv12, Default::default()),
+            Err(ConvertError::UnsupportedConversion(_, _))
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use std::marker::PhantomData;
+
+use thiserror::Error;
+
+use crate::bindings;
+use crate::buffer::Buffer;
+use crate::Surface;
+use crate::SurfaceMemoryDescriptor;
+use crate::VaError;
+
+/// Minimum VA-API version providing `vaCopy`.
+pub(crate) const VA_COPY_MIN_VERSION: (u32, u32) = (1, 12);
+
+/// Error type for [`crate::Display::copy`].
+#[derive(Debug, Error)]
+pub enum CopyError {
+    #[error("vaCopy requires VA-API 1.12 but the display only supports {0}.{1}")]
+    Unsupported(u32, u32),
+    #[error("call to vaCopy failed: {0}")]
+    VaError(#[from] VaError),
+}
+
+/// Source or destination of a [`crate::Display::copy`], i.e. a `VACopyObject`.
+pub struct CopyObject<'a> {
+    object: bindings::VACopyObject,
+    /// The surface or buffer this object refers to must outlive it.
+    phantom: PhantomData<&'a ()>,
+}
+
+impl<'a> CopyObject<'a> {
+    /// Creates a copy object referring to `surface`.
+    pub fn surface<D: SurfaceMemoryDescriptor>(surface: &'a Surface<D>) -> Self {
+        Self {
+            object: bindings::VACopyObject {
+                obj_type: bindings::VACopyObjType::VACopyObjSurface,
+                object: bindings::_VACopyObject__bindgen_ty_1 {
+                    surface_id: surface.id(),
+                },
+                ..Default::default()
+            },
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a copy object referring to `buffer`.
+    pub fn buffer(buffer: &'a Buffer) -> Self {
+        Self {
+            object: bindings::VACopyObject {
+                obj_type: bindings::VACopyObjType::VACopyObjBuffer,
+                object: bindings::_VACopyObject__bindgen_ty_1 {
+                    buffer_id: buffer.id(),
+                },
+                ..Default::default()
+            },
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the inner FFI type.
+    pub(crate) fn inner_mut(&mut self) -> &mut bindings::VACopyObject {
+        &mut self.object
+    }
+}
+
+/// Whether [`crate::Display::copy`] waits for the copy to complete.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CopySync {
+    /// Return once the copy is complete (`VA_EXEC_SYNC`).
+    #[default]
+    Blocking,
+    /// Return as soon as the copy is submitted (`VA_EXEC_ASYNC`). Synchronizing on the destination
+    /// waits for the copy to complete.
+    NonBlocking,
+}
+
+/// Engine used to perform a [`crate::Display::copy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CopyMode {
+    /// Let the driver decide (`VA_EXEC_MODE_DEFAULT`).
+    #[default]
+    Default,
+    /// Favor power efficiency, e.g. by using a blitter or copy engine
+    /// (`VA_EXEC_MODE_POWER_SAVING`).
+    PowerSaving,
+    /// Favor speed, e.g. by using the media engine (`VA_EXEC_MODE_PERFORMANCE`).
+    Performance,
+}
+
+/// Options of a [`crate::Display::copy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CopyOptions {
+    pub sync: CopySync,
+    pub mode: CopyMode,
+}
+
+impl CopyOptions {
+    /// Returns the `VACopyOption` corresponding to these options.
+    pub(crate) fn inner(&self) -> bindings::VACopyOption {
+        let sync = match self.sync {
+            CopySync::Blocking => bindings::VA_EXEC_SYNC,
+            CopySync::NonBlocking => bindings::VA_EXEC_ASYNC,
+        };
+        let mode = match self.mode {
+            CopyMode::Default => bindings::VA_EXEC_MODE_DEFAULT,
+            CopyMode::PowerSaving => bindings::VA_EXEC_MODE_POWER_SAVING,
+            CopyMode::Performance => bindings::VA_EXEC_MODE_PERFORMANCE,
+        };
+
+        let _bitfield_1 = bindings::_VACopyOption__bindgen_ty_1::new_bitfield_1(sync, mode, 0);
+
+        bindings::VACopyOption {
+            bits: bindings::_VACopyOption__bindgen_ty_1 {
+                _bitfield_align_1: Default::default(),
+                _bitfield_1,
+            },
+        }
+    }
+}
@@ -15,8 +15,19 @@ use thiserror::Error;
 use crate::bindings;
 use crate::config::Config;
 use crate::context::Context;
+use crate::copy::CopyError;
+use crate::copy::CopyObject;
+use crate::copy::CopyOptions;
+use crate::copy::VA_COPY_MIN_VERSION;
+use crate::image::OwnedImage;
 use crate::surface::Surface;
 use crate::va_check;
+use crate::ContextPriorityCaps;
+use crate::EncIntraRefresh;
+use crate::EncPackedHeaders;
+use crate::EncROICaps;
+use crate::EncRateControlExtCaps;
+use crate::SubpictureFlags;
 use crate::SurfaceMemoryDescriptor;
 use crate::UsageHint;
 use crate::VaError;
@@ -72,6 +83,8 @@ impl Iterator for DrmDeviceIterator {
 pub struct Display {
     /// Handle to interact with the underlying `VADisplay`.
     handle: bindings::VADisplay,
+    /// VA-API version supported by the display, as returned by `vaInitialize`.
+    version: (u32, u32),
     /// DRM file that must be kept open while the display is in use.
     #[allow(dead_code)]
     drm_file: File,
@@ -114,6 +127,7 @@ impl Display {
             .map(|()| {
                 Rc::new(Self {
                     handle: display,
+                    version: (major as u32, minor as u32),
                     drm_file: file,
                 })
             })
@@ -142,6 +156,11 @@ impl Display {
         self.handle
     }
 
+    /// Returns the `(major, minor)` VA-API version supported by this display.
+    pub fn version(&self) -> (u32, u32) {
+        self.version
+    }
+
     /// Queries supported profiles by this display by wrapping `vaQueryConfigProfiles`.
     pub fn query_config_profiles(&self) -> Result<Vec<bindings::VAProfile::Type>, VaError> {
         // Safe because `self` represents a valid VADisplay.
@@ -241,6 +260,124 @@ impl Display {
         })
     }
 
+    /// Returns the value of the `type_` attribute for a given `profile`/`entrypoint` pair, or
+    /// `None` if the attribute is not supported. This is a convenience wrapper over
+    /// [`Display::get_config_attributes`] for a single attribute.
+    pub fn get_config_attribute(
+        &self,
+        profile: bindings::VAProfile::Type,
+        entrypoint: bindings::VAEntrypoint::Type,
+        type_: bindings::VAConfigAttribType::Type,
+    ) -> Result<Option<u32>, VaError> {
+        let mut attributes = [bindings::VAConfigAttrib { type_, value: 0 }];
+        self.get_config_attributes(profile, entrypoint, &mut attributes)?;
+
+        Ok(match attributes[0].value {
+            bindings::VA_ATTRIB_NOT_SUPPORTED => None,
+            value => Some(value),
+        })
+    }
+
+    /// Returns the packed headers that can be provided by the application when encoding with a
+    /// given `profile`/`entrypoint` pair, as reported by `VAConfigAttribEncPackedHeaders`.
+    pub fn query_enc_packed_headers(
+        &self,
+        profile: bindings::VAProfile::Type,
+        entrypoint: bindings::VAEntrypoint::Type,
+    ) -> Result<EncPackedHeaders, VaError> {
+        Ok(self
+            .get_config_attribute(
+                profile,
+                entrypoint,
+                bindings::VAConfigAttribType::VAConfigAttribEncPackedHeaders,
+            )?
+            .map(EncPackedHeaders::from_bits_truncate)
+            .unwrap_or(EncPackedHeaders::empty()))
+    }
+
+    /// Returns the region-of-interest capabilities of a given `profile`/`entrypoint` pair, as
+    /// reported by `VAConfigAttribEncROI`, or `None` if ROI encoding is not supported.
+    pub fn query_enc_roi_caps(
+        &self,
+        profile: bindings::VAProfile::Type,
+        entrypoint: bindings::VAEntrypoint::Type,
+    ) -> Result<Option<EncROICaps>, VaError> {
+        Ok(self
+            .get_config_attribute(
+                profile,
+                entrypoint,
+                bindings::VAConfigAttribType::VAConfigAttribEncROI,
+            )?
+            .map(EncROICaps::from_attribute)
+            .filter(|caps| caps.num_roi_regions > 0))
+    }
+
+    /// Returns the rate control extension capabilities of a given `profile`/`entrypoint` pair,
+    /// including the maximum number of temporal layers, as reported by
+    /// `VAConfigAttribEncRateControlExt`, or `None` if the attribute is not supported.
+    pub fn query_enc_rate_control_ext_caps(
+        &self,
+        profile: bindings::VAProfile::Type,
+        entrypoint: bindings::VAEntrypoint::Type,
+    ) -> Result<Option<EncRateControlExtCaps>, VaError> {
+        Ok(self
+            .get_config_attribute(
+                profile,
+                entrypoint,
+                bindings::VAConfigAttribType::VAConfigAttribEncRateControlExt,
+            )?
+            .map(EncRateControlExtCaps::from_attribute))
+    }
+
+    /// Returns the intra refresh modes supported by a given `profile`/`entrypoint` pair, as
+    /// reported by `VAConfigAttribEncIntraRefresh`.
+    pub fn query_enc_intra_refresh(
+        &self,
+        profile: bindings::VAProfile::Type,
+        entrypoint: bindings::VAEntrypoint::Type,
+    ) -> Result<EncIntraRefresh, VaError> {
+        Ok(self
+            .get_config_attribute(
+                profile,
+                entrypoint,
+                bindings::VAConfigAttribType::VAConfigAttribEncIntraRefresh,
+            )?
+            .map(EncIntraRefresh::from_bits_truncate)
+            .unwrap_or(EncIntraRefresh::empty()))
+    }
+
+    /// Returns the size in pixels of the blocks of a QP map (see [`crate::EncQPMap`]) for a given
+    /// `profile`/`entrypoint` pair, as reported by `VAConfigAttribQPBlockSize`, or `None` if QP
+    /// maps are not supported.
+    pub fn query_qp_block_size(
+        &self,
+        profile: bindings::VAProfile::Type,
+        entrypoint: bindings::VAEntrypoint::Type,
+    ) -> Result<Option<u32>, VaError> {
+        self.get_config_attribute(
+            profile,
+            entrypoint,
+            bindings::VAConfigAttribType::VAConfigAttribQPBlockSize,
+        )
+    }
+
+    /// Returns the context priorities supported by a given `profile`/`entrypoint` pair, as
+    /// reported by `VAConfigAttribContextPriority`, or `None` if context priorities are not
+    /// supported.
+    pub fn query_context_priority_caps(
+        &self,
+        profile: bindings::VAProfile::Type,
+        entrypoint: bindings::VAEntrypoint::Type,
+    ) -> Result<Option<ContextPriorityCaps>, VaError> {
+        Ok(self
+            .get_config_attribute(
+                profile,
+                entrypoint,
+                bindings::VAConfigAttribType::VAConfigAttribContextPriority,
+            )?
+            .map(ContextPriorityCaps::from_attribute))
+    }
+
     /// Creates `Surface`s by wrapping around a `vaCreateSurfaces` call.
     ///
     /// The number of surfaces created will be equal to the length of `descriptors`.
@@ -324,6 +461,20 @@ impl Display {
         Config::new(Rc::clone(self), attrs, profile, entrypoint)
     }
 
+    /// Creates an [`OwnedImage`] of `width`x`height` pixels in `format` by wrapping around a
+    /// `vaCreateImage` call.
+    ///
+    /// The image is not tied to any surface and can be used to transfer data from and to any
+    /// surface of this display.
+    pub fn create_image(
+        self: &Rc<Self>,
+        format: bindings::VAImageFormat,
+        width: u32,
+        height: u32,
+    ) -> Result<OwnedImage, VaError> {
+        OwnedImage::new(Rc::clone(self), format, width, height)
+    }
+
     /// Returns available image formats for this display by wrapping around `vaQueryImageFormats`.
     pub fn query_image_formats(&self) -> Result<Vec<bindings::VAImageFormat>, VaError> {
         // Safe because `self` represents a valid VADisplay.
@@ -349,6 +500,76 @@ impl Display {
 
         Ok(image_formats)
     }
+
+    /// Returns the image formats supported for subpictures by this display, along with the
+    /// [`SubpictureFlags`] supported for each format, by wrapping around
+    /// `vaQuerySubpictureFormats`.
+    pub fn query_subpicture_formats(
+        &self,
+    ) -> Result<Vec<(bindings::VAImageFormat, SubpictureFlags)>, VaError> {
+        // Safe because `self` represents a valid VADisplay.
+        let max_num_formats = unsafe { bindings::vaMaxNumSubpictureFormats(self.handle) };
+        let mut formats = Vec::with_capacity(max_num_formats as usize);
+        let mut flags = Vec::with_capacity(max_num_formats as usize);
+        let mut num_formats = max_num_formats as u32;
+
+        // Safe because `self` represents a valid VADisplay. Both vectors have a capacity of
+        // `vaMaxNumSubpictureFormats` entries, which is the most the C function can write.
+        va_check(unsafe {
+            bindings::vaQuerySubpictureFormats(
+                self.handle,
+                formats.as_mut_ptr(),
+                flags.as_mut_ptr(),
+                &mut num_formats,
+            )
+        })?;
+
+        // Safe because the C function will have written exactly `num_formats` entries into both
+        // vectors, which is known to be within their capacity.
+        unsafe {
+            formats.set_len(num_formats as usize);
+            flags.set_len(num_formats as usize);
+        }
+
+        Ok(formats
+            .into_iter()
+            .zip(flags)
+            .map(|(format, flags)| (format, SubpictureFlags::from_bits_truncate(flags)))
+            .collect())
+    }
+
+    /// Copies the content of `src` into `dst` on the GPU by wrapping around `vaCopy`.
+    ///
+    /// Surfaces and buffers can be used as source and destination, which allows e.g. duplicating
+    /// a decoded frame without going through the CPU. `options` control whether the call blocks
+    /// until the copy is complete and which engine performs it.
+    ///
+    /// `vaCopy` is only available from VA-API 1.12, and [`CopyError::Unsupported`] is returned if
+    /// this display is older. Drivers not implementing it return
+    /// `VA_STATUS_ERROR_UNIMPLEMENTED`.
+    pub fn copy(
+        &self,
+        mut dst: CopyObject,
+        mut src: CopyObject,
+        options: CopyOptions,
+    ) -> Result<(), CopyError> {
+        if self.version < VA_COPY_MIN_VERSION {
+            return Err(CopyError::Unsupported(self.version.0, self.version.1));
+        }
+
+        // Safe because `self` represents a valid VADisplay, and `dst` and `src` refer to objects
+        // that are alive for the duration of the call.
+        va_check(unsafe {
+            bindings::vaCopy(
+                self.handle,
+                dst.inner_mut(),
+                src.inner_mut(),
+                options.inner(),
+            )
+        })?;
+
+        Ok(())
+    }
 }
 
 impl Drop for Display {
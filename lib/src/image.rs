@@ -37,6 +37,35 @@ pub struct Image<'a> {
     dirty: bool,
     /// The ID of the `Surface` we have been created from.
     surface_id: u32,
+    /// Source rectangle inside the image and destination rectangle inside the surface used to
+    /// write the image back with `vaPutImage` if it has been written to.
+    put_region: (bindings::VARectangle, bindings::VARectangle),
+}
+
+/// Returns a `VARectangle` with origin `(x, y)` and size `(width, height)`.
+fn rect(x: i16, y: i16, width: u16, height: u16) -> bindings::VARectangle {
+    bindings::VARectangle {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Trait for types giving access to pixel data laid out as described by a `VAImage`, i.e. with
+/// the planes at `offsets` and rows separated by `pitches` bytes.
+pub trait ImageData {
+    /// Returns the `VAImage` describing the layout of the data.
+    fn va_image(&self) -> &bindings::VAImage;
+
+    /// Returns the pixel data.
+    fn data(&self) -> &[u8];
+}
+
+/// Trait for [`ImageData`] types whose pixel data can be written to.
+pub trait ImageDataMut: ImageData {
+    /// Returns the pixel data for writing.
+    fn data_mut(&mut self) -> &mut [u8];
 }
 
 impl<'a> Image<'a> {
@@ -48,6 +77,7 @@ impl<'a> Image<'a> {
         image: bindings::VAImage,
         derived: bool,
         display_resolution: (u32, u32),
+        put_region: (bindings::VARectangle, bindings::VARectangle),
     ) -> Result<Self, VaError> {
         let mut addr = std::ptr::null_mut();
 
@@ -75,6 +105,7 @@ impl<'a> Image<'a> {
                     display_resolution,
                     dirty: false,
                     surface_id: surface.id(),
+                    put_region,
                 })
             }
             Err(e) => {
@@ -107,7 +138,8 @@ impl<'a> Image<'a> {
             bindings::vaDeriveImage(surface.display().handle(), surface.id(), &mut image)
         })?;
 
-        Self::new(surface, image, true, visible_rect)
+        let full = rect(0, 0, image.width, image.height);
+        Self::new(surface, image, true, visible_rect, (full, full))
     }
 
     /// Create new image from `surface` using `vaCreateImage` and `vaGetImage`.
@@ -118,10 +150,51 @@ impl<'a> Image<'a> {
     /// `coded_resolution`, meaning the data can be scaled if `coded_resolution` and `visible_rect`
     /// differ.
     pub fn create_from<D: SurfaceMemoryDescriptor>(
+        surface: &'a Surface<D>,
+        format: bindings::VAImageFormat,
+        coded_resolution: (u32, u32),
+        visible_rect: (u32, u32),
+    ) -> Result<Image, VaError> {
+        Self::create_with_regions(surface, format, coded_resolution, visible_rect, None, None)
+    }
+
+    /// Create new image from the `region` of `surface` using `vaCreateImage` and `vaGetImage`.
+    ///
+    /// The image has the size of `region` and contains a copy of the surface's data inside that
+    /// rectangle, e.g. a single tile or a crop of the surface. If the image is written to, it is
+    /// written back into the same `region` of the surface when dropped. Use
+    /// [`Image::set_put_region`] to write it elsewhere or to scale it.
+    pub fn create_from_region<D: SurfaceMemoryDescriptor>(
+        surface: &'a Surface<D>,
+        format: bindings::VAImageFormat,
+        region: bindings::VARectangle,
+    ) -> Result<Image, VaError> {
+        let size = (u32::from(region.width), u32::from(region.height));
+        let src = rect(0, 0, region.width, region.height);
+
+        Self::create_with_regions(
+            surface,
+            format,
+            size,
+            size,
+            Some(region),
+            Some((src, region)),
+        )
+    }
+
+    /// Creates an image of `coded_resolution` in `format` and fills it with the `get_region` of
+    /// `surface`. If `get_region` is `None`, the `coded_resolution` area at the origin of the
+    /// surface is read.
+    ///
+    /// `put_region` is the initial value for the rectangles used to write the image back. If
+    /// `None`, the whole image is written back at the origin of the surface.
+    fn create_with_regions<D: SurfaceMemoryDescriptor>(
         surface: &'a Surface<D>,
         mut format: bindings::VAImageFormat,
         coded_resolution: (u32, u32),
         visible_rect: (u32, u32),
+        get_region: Option<bindings::VARectangle>,
+        put_region: Option<(bindings::VARectangle, bindings::VARectangle)>,
     ) -> Result<Image, VaError> {
         // An all-zero byte-pattern is a valid initial value for `VAImage`.
         let mut image: bindings::VAImage = Default::default();
@@ -138,20 +211,27 @@ impl<'a> Image<'a> {
             )
         })?;
 
+        let (x, y, width, height) = match get_region {
+            Some(region) => (
+                region.x.into(),
+                region.y.into(),
+                region.width.into(),
+                region.height.into(),
+            ),
+            None => (0, 0, coded_resolution.0, coded_resolution.1),
+        };
+
         // Safe because `dpy` is a valid display handle, `picture.surface` is a valid VASurface and
         // `image` is a valid `VAImage`.
         match va_check(unsafe {
-            bindings::vaGetImage(
-                dpy,
-                surface.id(),
-                0,
-                0,
-                coded_resolution.0,
-                coded_resolution.1,
-                image.image_id,
-            )
+            bindings::vaGetImage(dpy, surface.id(), x, y, width, height, image.image_id)
         }) {
-            Ok(()) => Self::new(surface, image, false, visible_rect),
+            Ok(()) => {
+                let full = rect(0, 0, image.width, image.height);
+                let put_region = put_region.unwrap_or((full, full));
+
+                Self::new(surface, image, false, visible_rect, put_region)
+            }
 
             Err(e) => {
                 // Safe because `image` is a valid `VAImage`.
@@ -164,6 +244,22 @@ impl<'a> Image<'a> {
         }
     }
 
+    /// Sets the rectangles used to write this image back into its surface when it is dropped after
+    /// having been written to.
+    ///
+    /// `src` is the rectangle to read from inside the image, and `dst` the rectangle to write to
+    /// inside the surface. The data is scaled by `vaPutImage` if both rectangles have different
+    /// sizes. This has no effect on derived images, which are written to directly.
+    pub fn set_put_region(&mut self, src: bindings::VARectangle, dst: bindings::VARectangle) {
+        self.put_region = (src, dst);
+    }
+
+    /// Returns the source and destination rectangles used to write this image back into its
+    /// surface.
+    pub fn put_region(&self) -> (bindings::VARectangle, bindings::VARectangle) {
+        self.put_region
+    }
+
     /// Get a reference to the underlying `VAImage` that describes this image.
     pub fn image(&self) -> &bindings::VAImage {
         &self.image
@@ -201,25 +297,43 @@ impl<'a> AsMut<[u8]> for Image<'a> {
     }
 }
 
+impl<'a> ImageData for Image<'a> {
+    fn va_image(&self) -> &bindings::VAImage {
+        &self.image
+    }
+
+    fn data(&self) -> &[u8] {
+        self.as_ref()
+    }
+}
+
+impl<'a> ImageDataMut for Image<'a> {
+    fn data_mut(&mut self) -> &mut [u8] {
+        self.as_mut()
+    }
+}
+
 impl<'a> Drop for Image<'a> {
     fn drop(&mut self) {
         if !self.derived && self.dirty {
             // Safe because `picture.inner.context` represents a valid `VAContext`,
             // `picture.surface` represents a valid `VASurface` and `image` represents a valid
             // `VAImage`.
+            let (src, dst) = self.put_region;
+
             unsafe {
                 bindings::vaPutImage(
                     self.display.handle(),
                     self.surface_id,
                     self.image.image_id,
-                    0,
-                    0,
-                    self.image.width as u32,
-                    self.image.height as u32,
-                    0,
-                    0,
-                    self.image.width as u32,
-                    self.image.height as u32,
+                    src.x.into(),
+                    src.y.into(),
+                    src.width.into(),
+                    src.height.into(),
+                    dst.x.into(),
+                    dst.y.into(),
+                    dst.width.into(),
+                    dst.height.into(),
                 );
             }
         }
@@ -233,3 +347,192 @@ impl<'a> Drop for Image<'a> {
         }
     }
 }
+
+/// Wrapper around a `VAImage` created with `vaCreateImage` that is not tied to any `Surface`.
+///
+/// Contrary to [`Image`], an owned image does not borrow the surface it is transferred from or to.
+/// It can be filled from any surface using [`OwnedImage::get_from`], written to any surface using
+/// [`OwnedImage::put_to`], and mapped independently using [`OwnedImage::map`]. This allows an
+/// image to be created once and reused across frames as a staging area.
+pub struct OwnedImage {
+    /// The display from which the image was created, so we can destroy it upon destruction.
+    display: Rc<Display>,
+    /// The `VAImage` returned by libva.
+    image: bindings::VAImage,
+}
+
+impl OwnedImage {
+    /// Creates a new image of `width`x`height` pixels in `format` by wrapping around a
+    /// `vaCreateImage` call. This is just a helper for [`Display::create_image`].
+    pub(crate) fn new(
+        display: Rc<Display>,
+        mut format: bindings::VAImageFormat,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, VaError> {
+        // An all-zero byte-pattern is a valid initial value for `VAImage`.
+        let mut image: bindings::VAImage = Default::default();
+
+        // Safe because `display` is a valid display handle.
+        va_check(unsafe {
+            bindings::vaCreateImage(
+                display.handle(),
+                &mut format,
+                width as i32,
+                height as i32,
+                &mut image,
+            )
+        })?;
+
+        Ok(Self { display, image })
+    }
+
+    /// Copies the `region` of `surface` into the top-left corner of this image using `vaGetImage`.
+    pub fn get_from<D: SurfaceMemoryDescriptor>(
+        &mut self,
+        surface: &Surface<D>,
+        region: bindings::VARectangle,
+    ) -> Result<(), VaError> {
+        // Safe because `self.display` is a valid display handle, `surface` is a valid VASurface
+        // and `self.image` is a valid `VAImage`.
+        va_check(unsafe {
+            bindings::vaGetImage(
+                self.display.handle(),
+                surface.id(),
+                region.x.into(),
+                region.y.into(),
+                region.width.into(),
+                region.height.into(),
+                self.image.image_id,
+            )
+        })
+    }
+
+    /// Copies the `src` rectangle of this image into the `dst` rectangle of `surface` using
+    /// `vaPutImage`. The data is scaled if both rectangles have different sizes.
+    pub fn put_to<D: SurfaceMemoryDescriptor>(
+        &self,
+        surface: &Surface<D>,
+        src: bindings::VARectangle,
+        dst: bindings::VARectangle,
+    ) -> Result<(), VaError> {
+        // Safe because `self.display` is a valid display handle, `surface` is a valid VASurface
+        // and `self.image` is a valid `VAImage`.
+        va_check(unsafe {
+            bindings::vaPutImage(
+                self.display.handle(),
+                surface.id(),
+                self.image.image_id,
+                src.x.into(),
+                src.y.into(),
+                src.width.into(),
+                src.height.into(),
+                dst.x.into(),
+                dst.y.into(),
+                dst.width.into(),
+                dst.height.into(),
+            )
+        })
+    }
+
+    /// Maps the image data into our address space using `vaMapBuffer`.
+    ///
+    /// The data is unmapped when the returned [`MappedImage`] is dropped.
+    pub fn map(&mut self) -> Result<MappedImage, VaError> {
+        let mut addr = std::ptr::null_mut();
+
+        // Safe because `self.display` is a valid display handle and `self.image.buf` is the
+        // buffer of a valid `VAImage`.
+        va_check(unsafe {
+            bindings::vaMapBuffer(self.display.handle(), self.image.buf, &mut addr)
+        })?;
+
+        // Safe since `addr` points to data mapped onto our address space since we called
+        // `vaMapBuffer` above, which also guarantees that the data is valid for
+        // `image.data_size`.
+        let data =
+            unsafe { std::slice::from_raw_parts_mut(addr as _, self.image.data_size as usize) };
+
+        Ok(MappedImage { image: self, data })
+    }
+
+    /// Get a reference to the underlying `VAImage` that describes this image.
+    pub fn image(&self) -> &bindings::VAImage {
+        &self.image
+    }
+
+    /// Returns the resolution of this image. This value can be larger than the value passed in
+    /// when the image was created if the driver needs to.
+    pub fn coded_resolution(&self) -> (u32, u32) {
+        (self.image.width.into(), self.image.height.into())
+    }
+
+    /// Returns the display this image has been created from.
+    pub(crate) fn display(&self) -> &Rc<Display> {
+        &self.display
+    }
+}
+
+impl Drop for OwnedImage {
+    fn drop(&mut self) {
+        // Safe since `self.image` represents a valid `VAImage`.
+        unsafe {
+            bindings::vaDestroyImage(self.display.handle(), self.image.image_id);
+        }
+    }
+}
+
+/// The data of an [`OwnedImage`], mapped into our address space.
+///
+/// The data is unmapped when this object is dropped.
+pub struct MappedImage<'a> {
+    /// The image whose data is mapped.
+    image: &'a mut OwnedImage,
+    /// The mapped image data.
+    data: &'a mut [u8],
+}
+
+impl<'a> MappedImage<'a> {
+    /// Get a reference to the underlying `VAImage` that describes this image.
+    pub fn image(&self) -> &bindings::VAImage {
+        &self.image.image
+    }
+}
+
+impl<'a> AsRef<[u8]> for MappedImage<'a> {
+    fn as_ref(&self) -> &[u8] {
+        self.data
+    }
+}
+
+impl<'a> AsMut<[u8]> for MappedImage<'a> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+}
+
+impl<'a> ImageData for MappedImage<'a> {
+    fn va_image(&self) -> &bindings::VAImage {
+        &self.image.image
+    }
+
+    fn data(&self) -> &[u8] {
+        self.data
+    }
+}
+
+impl<'a> ImageDataMut for MappedImage<'a> {
+    fn data_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+}
+
+impl<'a> Drop for MappedImage<'a> {
+    fn drop(&mut self) {
+        // Safe since the buffer is mapped in `OwnedImage::map`, so `self.image.image.buf` points
+        // to a valid `VABufferID`.
+        unsafe {
+            bindings::vaUnmapBuffer(self.image.display.handle(), self.image.image.buf);
+        }
+    }
+}
@@ -9,12 +9,16 @@
 
 mod bindings;
 pub mod buffer;
+pub mod checksum;
 mod config;
 mod context;
+pub mod convert;
+mod copy;
 mod display;
 mod generic_value;
 mod image;
 mod picture;
+mod subpicture;
 mod surface;
 mod usage_hint;
 
@@ -24,10 +28,12 @@ pub use bindings::*;
 pub use buffer::*;
 pub use config::*;
 pub use context::*;
+pub use copy::*;
 pub use display::*;
 pub use generic_value::*;
 pub use image::*;
 pub use picture::*;
+pub use subpicture::*;
 pub use surface::*;
 pub use usage_hint::*;
 
@@ -265,6 +271,133 @@ mod tests {
         assert_eq!(crc_nv12_image(&image), 0xa5713e52);
     }
 
+    #[test]
+    // Ignore this test by default as it requires libva-compatible hardware.
+    #[ignore]
+    fn shared_buffer_update() {
+        let display = Display::open().unwrap();
+
+        let profile = bindings::VAProfile::VAProfileMPEG2Main;
+        let entrypoint = bindings::VAEntrypoint::VAEntrypointVLD;
+        let width = 16u32;
+        let height = 16u32;
+
+        let attrs = vec![bindings::VAConfigAttrib {
+            type_: bindings::VAConfigAttribType::VAConfigAttribRTFormat,
+            value: bindings::VA_RT_FORMAT_YUV420,
+        }];
+        let config = display.create_config(attrs, profile, entrypoint).unwrap();
+
+        let mut surfaces = display
+            .create_surfaces(
+                bindings::VA_RT_FORMAT_YUV420,
+                None,
+                width,
+                height,
+                Some(UsageHint::USAGE_HINT_DECODER),
+                vec![(), ()],
+            )
+            .unwrap();
+        let context = display
+            .create_context(&config, width, height, Some(&surfaces), true)
+            .unwrap();
+
+        let pic_param = |horizontal_size| {
+            let picture_coding_extension =
+                MPEG2PictureCodingExtension::new(0, 3, 0, 1, 0, 0, 0, 0, 0, 1, 1);
+            BufferType::PictureParameter(PictureParameter::MPEG2(PictureParameterBufferMPEG2::new(
+                horizontal_size,
+                16,
+                0xffffffff,
+                0xffffffff,
+                1,
+                0xffff,
+                &picture_coding_extension,
+            )))
+        };
+
+        let shared = Rc::new(context.create_buffer(pic_param(16)).unwrap());
+
+        let mut first = Picture::new(0, Rc::clone(&context), surfaces.remove(0));
+        first.add_shared_buffer(Rc::clone(&shared));
+
+        // The first picture still holds a reference to the buffer, which can nonetheless be
+        // updated for the next picture.
+        shared.update(pic_param(32)).unwrap();
+
+        let mut second = Picture::new(1, Rc::clone(&context), surfaces.remove(0));
+        second.add_shared_buffer(Rc::clone(&shared));
+
+        // `horizontal_size` is the first member of `VAPictureParameterBufferMPEG2`.
+        assert_eq!(shared.map::<u16>().unwrap()[0], 32);
+        assert_eq!(Rc::strong_count(&shared), 3);
+    }
+
+    #[test]
+    // Ignore this test by default as it requires libva-compatible hardware.
+    #[ignore]
+    fn enc_qp_map_pitch() {
+        let display = Display::open().unwrap();
+
+        let entrypoint = bindings::VAEntrypoint::VAEntrypointEncSliceLP;
+        let profile = bindings::VAProfile::VAProfileH264ConstrainedBaseline;
+        let width = 64u32;
+        let height = 64u32;
+
+        let block_size = display
+            .query_qp_block_size(profile, entrypoint)
+            .unwrap()
+            .expect("QP maps are not supported");
+
+        let attrs = vec![bindings::VAConfigAttrib {
+            type_: bindings::VAConfigAttribType::VAConfigAttribRTFormat,
+            value: bindings::VA_RT_FORMAT_YUV420,
+        }];
+        let config = display.create_config(attrs, profile, entrypoint).unwrap();
+
+        let surfaces = display
+            .create_surfaces(
+                bindings::VA_RT_FORMAT_YUV420,
+                None,
+                width,
+                height,
+                Some(UsageHint::USAGE_HINT_ENCODER),
+                vec![()],
+            )
+            .unwrap();
+        let context = display
+            .create_context(&config, width, height, Some(&surfaces), true)
+            .unwrap();
+
+        let mut qp_map = context
+            .create_enc_qp_map(block_size, width, height)
+            .unwrap();
+        let (map_width, map_height) = EncQPMap::dimensions(block_size, width, height);
+        assert_eq!((qp_map.width(), qp_map.height()), (map_width, map_height));
+
+        let qps: Vec<u8> = (0..map_width * map_height)
+            .map(|i| (i % 52) as u8)
+            .collect();
+        assert!(qp_map.map().unwrap().write(&qps));
+
+        let pitch = qp_map.pitch() as usize;
+        let unit_size = qp_map.unit_size() as usize;
+        assert!(pitch >= map_width as usize * unit_size);
+
+        // Safe because the driver allocates `pitch` bytes for each row of the map.
+        let mapping = unsafe {
+            qp_map
+                .buffer()
+                .map_mut_with_len(pitch * map_height as usize)
+                .unwrap()
+        };
+        for (y, row) in qps.chunks(map_width as usize).enumerate() {
+            for (x, &qp) in row.iter().enumerate() {
+                assert_eq!(mapping[y * pitch + x * unit_size], qp);
+            }
+        }
+    }
+
     #[test]
     // Ignore this test by default as it requires libva-compatible hardware.
     #[ignore]
@@ -82,7 +82,7 @@ struct PictureInner<T> {
     /// A context associated with this picture.
     context: Rc<Context>,
     /// Contains the buffers used to decode the data.
-    buffers: Vec<Buffer>,
+    buffers: Vec<Rc<Buffer>>,
     /// Contains the actual decoded data. Note that the surface may be shared in
     /// interlaced decoding.
     surface: Rc<T>,
@@ -145,6 +145,15 @@ impl<T> Picture<PictureNew, T> {
 
     /// Add `buffer` to the picture.
     pub fn add_buffer(&mut self, buffer: Buffer) {
+        self.inner.buffers.push(Rc::new(buffer));
+    }
+
+    /// Add a `buffer` that can be shared with other pictures.
+    ///
+    /// Contrary to [`Picture::add_buffer`], the buffer is not destroyed along with the picture as
+    /// long as other references to it exist. This allows a parameter buffer to be created once,
+    /// updated in place with [`Buffer::update`] and submitted again with every picture.
+    pub fn add_shared_buffer(&mut self, buffer: Rc<Buffer>) {
         self.inner.buffers.push(buffer);
     }
 
@@ -181,7 +190,12 @@ impl<T> Picture<PictureBegin, T> {
             bindings::vaRenderPicture(
                 self.inner.context.display().handle(),
                 self.inner.context.id(),
-                Buffer::as_id_vec(&self.inner.buffers).as_mut_ptr(),
+                self.inner
+                    .buffers
+                    .iter()
+                    .map(|buffer| buffer.id())
+                    .collect::<Vec<_>>()
+                    .as_mut_ptr(),
                 self.inner.buffers.len() as i32,
             )
         })
@@ -298,6 +312,20 @@ impl<S: PictureReclaimableSurface, T> Picture<S, T> {
     {
         Image::create_from(self.surface(), format, coded_resolution, visible_rect)
     }
+
+    /// Create new image from the `region` of the `Picture` using `vaCreateImage` and `vaGetImage`.
+    ///
+    /// The image will contain a copy of the `region` of the `Picture` in the desired `format`.
+    pub fn create_image_from_region<'a, D: SurfaceMemoryDescriptor + 'a>(
+        &'a self,
+        format: bindings::VAImageFormat,
+        region: bindings::VARectangle,
+    ) -> Result<Image, VaError>
+    where
+        T: Borrow<Surface<D>>,
+    {
+        Image::create_from_region(self.surface(), format, region)
+    }
 }
 
 impl<S: PictureState, T> AsRef<T> for Picture<S, T> {
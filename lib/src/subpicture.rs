@@ -0,0 +1,203 @@
+// Copyright 2024 The ChromiumOS Authors
+// Use of this source code is governed by a BSD-style license that can be
+// found in the LICENSE file.
+
+use bitflags::bitflags;
+use log::error;
+
+use crate::bindings;
+use crate::va_check;
+use crate::OwnedImage;
+use crate::Surface;
+use crate::SurfaceMemoryDescriptor;
+use crate::VaError;
+
+bitflags! {
+    /// Flags controlling how a [`Subpicture`] is blended onto its surfaces.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SubpictureFlags: u32 {
+        /// Use the chroma key set with [`Subpicture::set_chromakey`].
+        const CHROMA_KEYING = bindings::VA_SUBPICTURE_CHROMA_KEYING;
+        /// Use the global alpha set with [`Subpicture::set_global_alpha`].
+        const GLOBAL_ALPHA = bindings::VA_SUBPICTURE_GLOBAL_ALPHA;
+        /// The destination rectangle is in screen coordinates instead of surface coordinates.
+        const DESTINATION_IS_SCREEN_COORD = bindings::VA_SUBPICTURE_DESTINATION_IS_SCREEN_COORD;
+    }
+}
+
+/// Wrapper around a subpicture created with `vaCreateSubpicture`.
+///
+/// A subpicture is an overlay (e.g. subtitles or an OSD) that is blended onto the surfaces it is
+/// associated with when they are rendered. Its content is the [`OwnedImage`] it has been created
+/// from, which can be updated at any time through [`Subpicture::image_mut`].
+///
+/// All associations are released and the subpicture destroyed when it is dropped.
+pub struct Subpicture {
+    /// The image providing the content of the subpicture.
+    image: OwnedImage,
+    /// The ID of the subpicture.
+    id: bindings::VASubpictureID,
+    /// Surfaces the subpicture is currently associated with.
+    associated: Vec<bindings::VASurfaceID>,
+}
+
+impl Subpicture {
+    /// Creates a subpicture using the content of `image` by wrapping around a
+    /// `vaCreateSubpicture` call.
+    ///
+    /// The format of `image` must be one of those returned by
+    /// [`crate::Display::query_subpicture_formats`].
+    pub fn new(image: OwnedImage) -> Result<Self, VaError> {
+        let mut id = 0;
+
+        // Safe because `image` is a valid `VAImage` of a valid display.
+        va_check(unsafe {
+            bindings::vaCreateSubpicture(image.display().handle(), image.image().image_id, &mut id)
+        })?;
+
+        Ok(Self {
+            image,
+            id,
+            associated: Default::default(),
+        })
+    }
+
+    /// Returns the image providing the content of this subpicture.
+    pub fn image(&self) -> &OwnedImage {
+        &self.image
+    }
+
+    /// Returns the image providing the content of this subpicture, e.g. to map and update it.
+    pub fn image_mut(&mut self) -> &mut OwnedImage {
+        &mut self.image
+    }
+
+    /// Returns the ID of this subpicture.
+    pub fn id(&self) -> bindings::VASubpictureID {
+        self.id
+    }
+
+    /// Sets the chroma key of the subpicture by wrapping around `vaSetSubpictureChromakey`.
+    ///
+    /// Pixels whose value, masked by `mask`, is between `min` and `max` are transparent. This only
+    /// applies to associations made with [`SubpictureFlags::CHROMA_KEYING`].
+    pub fn set_chromakey(&mut self, min: u32, max: u32, mask: u32) -> Result<(), VaError> {
+        // Safe because `self` represents a valid subpicture.
+        va_check(unsafe {
+            bindings::vaSetSubpictureChromakey(
+                self.image.display().handle(),
+                self.id,
+                min,
+                max,
+                mask,
+            )
+        })
+    }
+
+    /// Sets the global alpha of the subpicture, between `0.0` (transparent) and `1.0` (opaque), by
+    /// wrapping around `vaSetSubpictureGlobalAlpha`. This only applies to associations made with
+    /// [`SubpictureFlags::GLOBAL_ALPHA`].
+    pub fn set_global_alpha(&mut self, alpha: f32) -> Result<(), VaError> {
+        // Safe because `self` represents a valid subpicture.
+        va_check(unsafe {
+            bindings::vaSetSubpictureGlobalAlpha(self.image.display().handle(), self.id, alpha)
+        })
+    }
+
+    /// Associates the subpicture with `surfaces` by wrapping around `vaAssociateSubpicture`.
+    ///
+    /// The `src` rectangle of the subpicture is blended into the `dst` rectangle of each surface,
+    /// with scaling if both rectangles have different sizes.
+    pub fn associate<D: SurfaceMemoryDescriptor>(
+        &mut self,
+        surfaces: &[&Surface<D>],
+        src: bindings::VARectangle,
+        dst: bindings::VARectangle,
+        flags: SubpictureFlags,
+    ) -> Result<(), VaError> {
+        let mut ids: Vec<_> = surfaces.iter().map(|surface| surface.id()).collect();
+
+        // Safe because `self` represents a valid subpicture and `ids` contains valid surface IDs.
+        // The length of `ids` is passed to the C function, so it cannot read past its end.
+        va_check(unsafe {
+            bindings::vaAssociateSubpicture(
+                self.image.display().handle(),
+                self.id,
+                ids.as_mut_ptr(),
+                ids.len() as i32,
+                src.x,
+                src.y,
+                src.width,
+                src.height,
+                dst.x,
+                dst.y,
+                dst.width,
+                dst.height,
+                flags.bits(),
+            )
+        })?;
+
+        for id in ids {
+            if !self.associated.contains(&id) {
+                self.associated.push(id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes the association of the subpicture with `surfaces` by wrapping around
+    /// `vaDeassociateSubpicture`.
+    pub fn deassociate<D: SurfaceMemoryDescriptor>(
+        &mut self,
+        surfaces: &[&Surface<D>],
+    ) -> Result<(), VaError> {
+        let mut ids: Vec<_> = surfaces.iter().map(|surface| surface.id()).collect();
+
+        // Safe because `self` represents a valid subpicture and `ids` contains valid surface IDs.
+        // The length of `ids` is passed to the C function, so it cannot read past its end.
+        va_check(unsafe {
+            bindings::vaDeassociateSubpicture(
+                self.image.display().handle(),
+                self.id,
+                ids.as_mut_ptr(),
+                ids.len() as i32,
+            )
+        })?;
+
+        self.associated.retain(|id| !ids.contains(id));
+
+        Ok(())
+    }
+}
+
+impl Drop for Subpicture {
+    fn drop(&mut self) {
+        let handle = self.image.display().handle();
+
+        if !self.associated.is_empty() {
+            // Safe because `self` represents a valid subpicture and `self.associated` the surfaces
+            // it has been associated with.
+            let status = va_check(unsafe {
+                bindings::vaDeassociateSubpicture(
+                    handle,
+                    self.id,
+                    self.associated.as_mut_ptr(),
+                    self.associated.len() as i32,
+                )
+            });
+
+            if let Err(e) = status {
+                error!("vaDeassociateSubpicture failed: {}", e);
+            }
+        }
+
+        // Safe because `self` represents a valid subpicture. The image is destroyed after it, when
+        // the fields of `self` are dropped.
+        let status = va_check(unsafe { bindings::vaDestroySubpicture(handle, self.id) });
+
+        if let Err(e) = status {
+            error!("vaDestroySubpicture failed: {}", e);
+        }
+    }
+}